@@ -0,0 +1,54 @@
+//! Checks that parsing any args returns `Ok` or `Err` and never panics.
+//!
+//! Run with `cargo +nightly fuzz run parse` from the repository root.
+#![no_main]
+
+use ecp::builder::{App, Command, DuplicateKeys, Flag};
+use libfuzzer_sys::fuzz_target;
+use std::ffi;
+
+fn app(settings: u8) -> App {
+    App::new("fuzz")
+        .allow_prefixes(settings & 1 != 0)
+        .allow_unknown(settings & 2 != 0)
+        .command(
+            Command::new("cargo")
+                .alias("c")
+                .flag(Flag::new("verbose").short('v').negatable(true))
+                .subcommand(
+                    Command::new("build")
+                        .visible_alias("b")
+                        .flag(Flag::new("release").short('r').alias("optimized"))
+                        .flag(Flag::new("jobs").short('j').takes_value(true).max_occurrences(1))
+                        .flag(Flag::new("features").value_delimiter(','))
+                        .flag(
+                            Flag::new("config")
+                                .short('D')
+                                .key_value(true)
+                                .duplicate_keys(DuplicateKeys::Collect),
+                        )
+                        .flag(Flag::new("color").default_missing_value("always")),
+                )
+                .subcommand(
+                    Command::new("run")
+                        .allow_hyphen_values(settings & 4 != 0)
+                        .flag(Flag::new("args").takes_value(true).allow_hyphen_values(true))
+                        .flag(Flag::new("offset").short('1').takes_value(true)),
+                ),
+        )
+        .command(Command::new("ls").flag(Flag::new("long").short('l').min_occurrences(1)))
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((settings, data)) = data.split_first() else {
+        return;
+    };
+
+    // Args are separated by NUL bytes, like in `/proc/<pid>/cmdline`
+    let args: Vec<ffi::OsString> = String::from_utf8_lossy(data)
+        .split('\0')
+        .map(ffi::OsString::from)
+        .collect();
+
+    let _ = app(*settings).try_parse_args(args);
+});
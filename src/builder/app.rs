@@ -1,10 +1,18 @@
 use std::env;
 use std::ffi;
+use std::io::{self, IsTerminal};
+use std::process::{self, ExitCode};
 
 use crate::builder::Command;
-use crate::errors::AppError;
-use crate::parser::CommandParsed;
+use crate::builder::validate;
+use crate::dispatch::{Context, ErrorHook, Hook, Hooks};
+use crate::errors::{AppError, Diagnostic, ErrorFormat, ErrorKind};
 use crate::parser::utils::*;
+use crate::parser::{CommandParsed, split_line};
+use crate::style::{ColorChoice, Styles};
+
+/// The width help is wrapped to when neither [`App::term_width()`] nor `$COLUMNS` are set.
+const DEFAULT_TERM_WIDTH: usize = 80;
 
 /// Contains all commands of the CLI app.
 ///
@@ -13,12 +21,26 @@ use crate::parser::utils::*;
 /// removing the need to manually handle user input.
 /// You can also use [`App::try_run()`] to manually handle errors that may occur during parsing.
 /// You can use [`App::parse_args()`] or [`App::try_parse_args()`] to manually handle user input.
-
 pub struct App {
     pub(crate) name: String,
     pub(crate) version: Option<String>,
     pub(crate) description: Option<String>,
     pub(crate) commands: Vec<Command>,
+    pub(crate) allow_prefixes: bool,
+    pub(crate) allow_unknown: bool,
+    pub(crate) no_binary_name: bool,
+    pub(crate) collect_errors: bool,
+    pub(crate) hooks: Hooks,
+    pub(crate) exit_codes: Vec<(ErrorKind, u8)>,
+    pub(crate) error_format: ErrorFormat,
+    pub(crate) styles: Styles,
+    pub(crate) color: ColorChoice,
+    pub(crate) term_width: Option<usize>,
+    pub(crate) examples: Vec<(String, String)>,
+    pub(crate) sections: Vec<(String, String)>,
+    pub(crate) help_template: Option<String>,
+    pub(crate) unstable_flag: Option<String>,
+    pub(crate) unstable_env: Option<String>,
 }
 
 impl App {
@@ -31,10 +53,26 @@ impl App {
             version: None,
             description: None,
             commands: Vec::new(),
+            allow_prefixes: false,
+            allow_unknown: false,
+            no_binary_name: false,
+            collect_errors: false,
+            hooks: Hooks::default(),
+            exit_codes: Vec::new(),
+            error_format: ErrorFormat::Text,
+            styles: Styles::default(),
+            color: ColorChoice::Auto,
+            term_width: None,
+            examples: Vec::new(),
+            sections: Vec::new(),
+            help_template: None,
+            unstable_flag: None,
+            unstable_env: None,
         }
     }
 
-    /// Sets the version.
+    /// Sets the version, printed by the built-in `--version` flag after any command unless the
+    /// command has a `version` flag of its own.
     pub fn version(mut self, version: &str) -> App {
         self.version = Some(version.to_string());
         self
@@ -46,6 +84,61 @@ impl App {
         self
     }
 
+    /// Accepts any unambiguous prefix of a command name, a long flag name or their aliases.
+    ///
+    /// With this enabled `cargo b --rel` is parsed as `cargo build --release`. A prefix that
+    /// matches more than one command or flag returns [`AppError::AmbiguousCommand`] or
+    /// [`AppError::AmbiguousFlag`] listing the candidates. Exact matches always take precedence,
+    /// and built-in flags like `--help` or `--version` are never taken by a prefix.
+    pub fn allow_prefixes(mut self, allow: bool) -> App {
+        self.allow_prefixes = allow;
+        self
+    }
+
+    /// Collects flags that don't match any flag of the command instead of returning an error.
+    ///
+    /// Unknown flags are kept in order, see [`CommandParsed::get_unknown_flags()`], so wrapper
+    /// tools can forward them to another program. In a cluster of short flags, the rest of the
+    /// cluster from the first unknown char is kept as one flag, like the `-O2` of `-vO2`.
+    pub fn allow_unknown(mut self, allow: bool) -> App {
+        self.allow_unknown = allow;
+        self
+    }
+
+    /// Treats the first arg as the command instead of the binary name.
+    ///
+    /// Useful when embedding the [`App`], where args don't come from [`std::env::args_os()`].
+    pub fn no_binary_name(mut self, no_binary_name: bool) -> App {
+        self.no_binary_name = no_binary_name;
+        self
+    }
+
+    /// Keeps parsing after recoverable errors and returns all of them at once in
+    /// [`AppError::Multiple`], so users can fix every mistake in one go.
+    ///
+    /// Unknown flags, missing and unexpected values, invalid `key=value` pairs and wrong numbers of
+    /// occurrences are recoverable. A command that can't be found stops parsing, since the args
+    /// after it can't be checked.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::errors::AppError;
+    ///
+    /// let app = App::new("Rust").collect_errors(true).command(
+    ///     Command::new("cargo").flag(Flag::new("jobs").takes_value(true)),
+    /// );
+    ///
+    /// match app.try_parse_from(["rust", "cargo", "--nope", "--jobs"]) {
+    ///     Err(AppError::Multiple(errors)) => assert_eq!(errors.len(), 2),
+    ///     _ => panic!(),
+    /// }
+    /// ```
+    pub fn collect_errors(mut self, collect: bool) -> App {
+        self.collect_errors = collect;
+        self
+    }
+
     /// Adds a command to the [`App`].
     ///
     /// # Example:
@@ -61,6 +154,284 @@ impl App {
         self.commands.push(command);
         self
     }
+
+    /// Sets the exit code used by [`App::report()`] for errors of `kind`, replacing its
+    /// [default](ErrorKind::default_exit_code).
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::errors::ErrorKind;
+    ///
+    /// let app = App::new("Rust").exit_code(ErrorKind::InvalidFlag, 2);
+    ///
+    /// assert_eq!(app.get_exit_code(ErrorKind::InvalidFlag), 2);
+    /// assert_eq!(app.get_exit_code(ErrorKind::InvalidCommand), 64);
+    /// ```
+    pub fn exit_code(mut self, kind: ErrorKind, code: u8) -> App {
+        self.exit_codes.retain(|(k, _)| *k != kind);
+        self.exit_codes.push((kind, code));
+        self
+    }
+
+    /// Adds an example invocation, shown in help, man pages and Markdown with its description.
+    pub fn example(mut self, invocation: &str, description: &str) -> App {
+        self.examples
+            .push((invocation.to_string(), description.to_string()));
+        self
+    }
+
+    /// Adds a section shown after the examples, like `Environment` or `See also`.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust")
+    ///     .command(Command::new("cargo"))
+    ///     .example("rust cargo", "Run the package manager")
+    ///     .section("Environment", "RUSTFLAGS  Extra flags passed to every compiler call");
+    ///
+    /// assert!(app.render_help().ends_with(concat!(
+    ///     "Examples:\n",
+    ///     "  $ rust cargo\n",
+    ///     "    Run the package manager\n",
+    ///     "\n",
+    ///     "Environment:\n",
+    ///     "  RUSTFLAGS  Extra flags passed to every compiler call\n",
+    /// )));
+    /// ```
+    pub fn section(mut self, heading: &str, body: &str) -> App {
+        self.sections.push((heading.to_string(), body.to_string()));
+        self
+    }
+
+    /// Sets the layout of help, for the app and every command without its own
+    /// [`Command::help_template()`].
+    ///
+    /// The placeholders are replaced with their part of the help, or nothing if it's empty:
+    /// `{name}`, `{version}`, `{description}`, `{usage}`, `{subcommands}`, `{flags}`,
+    /// `{examples}` and `{sections}`. The parts keep their headers, like `Flags:`.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust")
+    ///     .help_template("{usage}\n{flags}")
+    ///     .command(Command::new("cargo").flag(Flag::new("offline")));
+    ///
+    /// assert_eq!(
+    ///     app.render_command_help(&["cargo"]).unwrap(),
+    ///     "Usage: Rust cargo [FLAGS]\nFlags:\n      --offline\n"
+    /// );
+    /// ```
+    pub fn help_template(mut self, template: &str) -> App {
+        self.help_template = Some(template.to_string());
+        self
+    }
+
+    /// Adds a built-in `--<name>` flag that opens the unstable gate, so experimental commands and
+    /// flags are accepted. It can be given anywhere, unless a command has a flag called `name`.
+    ///
+    /// Without an open gate, using an experimental command or flag returns
+    /// [`AppError::Experimental`].
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::errors::AppError;
+    ///
+    /// let app = App::new("Rust")
+    ///     .unstable_flag("unstable")
+    ///     .command(Command::new("cargo").flag(Flag::new("build-std").experimental(true)));
+    ///
+    /// assert!(app.try_parse_from(["rust", "cargo", "--build-std", "--unstable"]).is_ok());
+    /// assert!(matches!(
+    ///     app.try_parse_from(["rust", "cargo", "--build-std"]),
+    ///     Err(AppError::Experimental(_))
+    /// ));
+    /// ```
+    pub fn unstable_flag(mut self, name: &str) -> App {
+        self.unstable_flag = Some(name.to_string());
+        self
+    }
+
+    /// Opens the unstable gate when the environment variable `var` is set to anything but an
+    /// empty string or `0`, see [`App::unstable_flag()`].
+    pub fn unstable_env(mut self, var: &str) -> App {
+        self.unstable_env = Some(var.to_string());
+        self
+    }
+
+    /// Sets how errors are printed by [`App::report()`], [`App::run()`] and the other `parse_*`
+    /// methods that exit, text by default.
+    ///
+    /// Users can also pick the format with the built-in `--error-format=<text|json>` flag, unless
+    /// a command has a flag called `error-format`. The flag only applies to the parse it's given
+    /// in, see [`CommandParsed::get_error_format()`] and [`Diagnostic::get_error_format()`].
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::errors::ErrorFormat;
+    ///
+    /// let app = App::new("Rust").command(Command::new("cargo"));
+    ///
+    /// let cli = app.try_parse_from(["rust", "--error-format=json", "cargo"]).unwrap();
+    /// assert_eq!(cli.get_error_format(), ErrorFormat::Json);
+    /// assert_eq!(app.get_error_format(), ErrorFormat::Text);
+    /// ```
+    pub fn error_format(mut self, format: ErrorFormat) -> App {
+        self.error_format = format;
+        self
+    }
+
+    /// Sets the styles of help and error output, like the colors of a brand palette.
+    pub fn styles(mut self, styles: Styles) -> App {
+        self.styles = styles;
+        self
+    }
+
+    /// Sets when help and error output is styled, [`ColorChoice::Auto`] by default.
+    ///
+    /// Users can also pick it with the built-in `--color=<auto|always|never>` flag, unless a
+    /// command has a flag called `color`. Like `--error-format`, the flag only applies to the
+    /// parse it's given in.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::style::ColorChoice;
+    ///
+    /// let app = App::new("Rust").color(ColorChoice::Never).command(Command::new("cargo"));
+    ///
+    /// let cli = app.try_parse_from(["rust", "--color=always", "cargo"]).unwrap();
+    /// assert_eq!(cli.get_color(), ColorChoice::Always);
+    /// assert_eq!(app.get_color(), ColorChoice::Never);
+    /// ```
+    pub fn color(mut self, color: ColorChoice) -> App {
+        self.color = color;
+        self
+    }
+
+    /// Sets the width help is wrapped to, instead of `$COLUMNS` or 80 columns.
+    ///
+    /// A fixed width keeps help the same on every terminal, like in snapshot tests.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").term_width(30).command(
+    ///     Command::new("cargo").description("Rust's package manager, it builds packages"),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     app.render_help(),
+    ///     "Rust\n\nUsage: Rust <COMMAND>\n\nCommands:\n  cargo  Rust's package\n         manager, it builds\n         packages\n"
+    /// );
+    /// ```
+    pub fn term_width(mut self, width: usize) -> App {
+        self.term_width = Some(width);
+        self
+    }
+
+    /// Adds a hook that runs before the hooks of the commands and the handler in
+    /// [`App::try_dispatch_from()`], like setting up logging from a `--verbose` flag.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::dispatch::Context;
+    ///
+    /// struct Verbose(bool);
+    ///
+    /// let app = App::new("Rust")
+    ///     .before(|cli, ctx| {
+    ///         ctx.insert(Verbose(cli.get_flags().any(|f| f == "verbose")));
+    ///         Ok(())
+    ///     })
+    ///     .command(Command::new("cargo").flag(Flag::new("verbose")).handler(|_, ctx| {
+    ///         assert!(ctx.get::<Verbose>().unwrap().0);
+    ///         Ok(())
+    ///     }));
+    ///
+    /// app.try_dispatch_from(["rust", "cargo", "--verbose"], &mut Context::new())
+    ///     .unwrap();
+    /// ```
+    pub fn before<F>(mut self, hook: F) -> App
+    where
+        F: Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        self.hooks.before.push(Hook::new(hook));
+        self
+    }
+
+    /// Adds a hook that runs after the handler and the hooks of the commands succeed in
+    /// [`App::try_dispatch_from()`].
+    pub fn after<F>(mut self, hook: F) -> App
+    where
+        F: Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        self.hooks.after.push(Hook::new(hook));
+        self
+    }
+
+    /// Adds a hook that receives the error of a hook or of the handler in
+    /// [`App::try_dispatch_from()`], after the hooks of the commands. Returning `Ok` handles the
+    /// error, returning `Err` passes it on to the next hook.
+    pub fn on_error<F>(mut self, hook: F) -> App
+    where
+        F: Fn(&CommandParsed, &mut Context, AppError) -> Result<(), AppError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.on_error.push(ErrorHook::new(hook));
+        self
+    }
+
+    /// Checks the [`App`] definition for duplicated commands and flags, colliding short flags and
+    /// invalid names.
+    ///
+    /// Every problem found is reported, not only the first one. In debug builds this is also run
+    /// by [`App::try_parse_args()`] before parsing.
+    ///
+    /// # Errors:
+    /// Returns [`AppError::InvalidDefinition`] containing a list of every
+    /// [`ValidationProblem`](crate::errors::ValidationProblem) found.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::errors::{AppError, ValidationProblem};
+    ///
+    /// let app = App::new("Rust")
+    ///     .command(Command::new("cargo"))
+    ///     .command(Command::new("cargo"));
+    ///
+    /// match app.validate() {
+    ///     Err(AppError::InvalidDefinition(problems)) => assert_eq!(
+    ///         problems,
+    ///         vec![ValidationProblem::DuplicateCommand {
+    ///             scope: "Rust".to_string(),
+    ///             name: "cargo".to_string(),
+    ///         }]
+    ///     ),
+    ///     _ => panic!("expected a duplicated command"),
+    /// }
+    /// ```
+    pub fn validate(&self) -> Result<(), AppError> {
+        let problems = validate::problems(self);
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::InvalidDefinition(problems))
+        }
+    }
+
     /// Attempts to returns a [`CommandParsed`] containing the user input broken into strings to
     /// simpler use.
     /// If you don't want to handle user input manually use [`App::run()`] method.
@@ -68,8 +439,16 @@ impl App {
     ///
     /// # Errors:
     /// This function will return an [`AppError`] if the args don't match the [`App`] fields.
+    /// In debug builds it will also return the errors found by [`App::validate()`].
     /// See the [`AppError`] struct for more info.
     ///
+    /// # Panics:
+    /// This function never panics, for any [`App`] and any args, including empty args, args
+    /// without a command and args that aren't valid UTF-8. Every input returns either `Ok` or
+    /// `Err`, this is checked by property tests over random apps and args and by the `parse`
+    /// fuzz target in `fuzz/`. Apps that fail [`App::validate()`] are covered by the release
+    /// build of the tests, since only there they reach the parser.
+    ///
     /// # Example:
     /// ```
     /// use std::ffi;
@@ -134,26 +513,7 @@ impl App {
     /// assert_eq!(app.get_values().any(|f| f == "8080"), true);
     /// ```
     pub fn try_parse_args(&self, args: Vec<ffi::OsString>) -> Result<CommandParsed, AppError> {
-        let args_utf8: Vec<String> = args
-            .iter()
-            .map(|arg| arg.to_string_lossy().into_owned())
-            .collect();
-
-        if args_utf8.len() < 2 {
-            return Err(AppError::InvalidInput("Too few arguments".to_string()));
-        }
-
-        let command = get_command(&self, &args_utf8)?;
-        let subcommand = get_subcommand(&self, &args_utf8)?;
-        let flags = get_flags(&self, &args_utf8)?;
-        let values = get_values(&args_utf8, &subcommand, &flags)?;
-
-        Ok(CommandParsed {
-            command,
-            subcommand,
-            flags,
-            values,
-        })
+        self.try_parse_from(args)
     }
 
     /// Returns a [`CommandParsed`] containing the user input broken into strings to simpler use.
@@ -220,15 +580,204 @@ impl App {
     /// assert_eq!(app.get_values().any(|f| f == "8080"), true);
     /// ```
     pub fn parse_args(&self, args: Vec<ffi::OsString>) -> CommandParsed {
-        match self.try_parse_args(args) {
-            Ok(parsed) => parsed,
-            Err(e) => e.exit(),
+        self.parse_from(args)
+    }
+
+    /// Attempts to return a [`CommandParsed`] from any iterator of args, like a `&str` array, a
+    /// `Vec<String>` or [`std::env::args_os()`].
+    /// The first arg is the binary name, unless [`App::no_binary_name()`] is set.
+    /// If you don't want to handle errors manually use [`App::parse_from()`].
+    ///
+    /// # Errors:
+    /// Same as [`App::try_parse_args()`], this function never panics.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo").subcommand(
+    ///         Command::new("run").flag(Flag::new("release").short('r')),
+    ///     ),
+    /// );
+    ///
+    /// let cli = app.try_parse_from(["ecp", "cargo", "run", "-r"]).unwrap();
+    ///
+    /// assert_eq!(cli.get_subcommand(), Some("run"));
+    /// assert!(cli.get_flags().any(|f| f == "release"));
+    /// ```
+    pub fn try_parse_from<I, T>(&self, args: I) -> Result<CommandParsed, AppError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        self.try_parse_diagnostics_from(args)
+            .map_err(|diagnostics| into_error(self, diagnostics))
+    }
+
+    /// Same as [`App::try_parse_from()`], but each error comes with the arg that caused it, its
+    /// position and the names the user may have meant, see [`Diagnostic`].
+    ///
+    /// Every error is returned if [`App::collect_errors()`] is set, otherwise only the first one.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo").flag(Flag::new("release")).flag(Flag::new("locked")),
+    /// );
+    ///
+    /// let diagnostics = app
+    ///     .try_parse_diagnostics_from(["rust", "cargo", "--relase"])
+    ///     .err()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(diagnostics[0].get_token(), Some("--relase"));
+    /// assert_eq!(diagnostics[0].get_position(), Some(2));
+    /// assert_eq!(diagnostics[0].get_suggestions(), ["--release"]);
+    /// ```
+    pub fn try_parse_diagnostics_from<I, T>(
+        &self,
+        args: I,
+    ) -> Result<CommandParsed, Vec<Diagnostic>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        #[cfg(debug_assertions)]
+        self.validate().map_err(|e| vec![Diagnostic::from(e)])?;
+
+        let args_utf8: Vec<String> = args
+            .into_iter()
+            .map(|arg| arg.into().to_string_lossy().into_owned())
+            .collect();
+
+        parse(self, &args_utf8)
+    }
+
+    /// Returns a [`CommandParsed`] from any iterator of args, like a `&str` array, a
+    /// `Vec<String>` or [`std::env::args_os()`].
+    /// If you want to manually handle errors use the method [`App::try_parse_from()`].
+    ///
+    /// # Errors:
+    /// This function will print a error if the args don't match the [`App`] fields.
+    /// See the [`AppError`] struct for more info.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let cli = App::new("Rust")
+    ///     .no_binary_name(true)
+    ///     .command(Command::new("cargo").subcommand(Command::new("build")))
+    ///     .parse_from(vec![String::from("cargo"), String::from("build")]);
+    ///
+    /// assert_eq!(cli.get_command(), "cargo");
+    /// assert_eq!(cli.get_subcommand(), Some("build"));
+    /// ```
+    pub fn parse_from<I, T>(&self, args: I) -> CommandParsed
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        match self.try_parse_diagnostics_from(args) {
+            Ok(parsed) => {
+                self.warn(&parsed);
+                parsed
+            }
+            Err(diagnostics) => self.exit(diagnostics),
+        }
+    }
+
+    /// Prints the warnings of `parsed` to stderr.
+    pub(crate) fn warn(&self, parsed: &CommandParsed) {
+        let styles = if parsed.color.enables_color(io::stderr().is_terminal()) {
+            self.styles
+        } else {
+            Styles::plain()
+        };
+
+        for warning in parsed.get_warnings() {
+            eprintln!("{} {}", styles.warning.paint("Warning:"), warning);
+        }
+    }
+
+    /// Prints the diagnostics and exits with their exit code.
+    fn exit(&self, diagnostics: Vec<Diagnostic>) -> ! {
+        process::exit(self.print_diagnostics(diagnostics).into())
+    }
+
+    /// Prints the diagnostics in the format and color picked for their parse, returning their
+    /// exit code.
+    fn print_diagnostics(&self, diagnostics: Vec<Diagnostic>) -> u8 {
+        let json = Diagnostic::list_to_json(&diagnostics);
+        let (format, color, styled_help) = match diagnostics.first() {
+            Some(d) => (d.error_format, d.color, d.styled_help.clone()),
+            None => (self.error_format, self.color, None),
+        };
+
+        // Ambiguous errors already list their candidates
+        let suggestions = match diagnostics.as_slice() {
+            [diagnostic] if diagnostic.error.candidates().is_empty() => {
+                diagnostic.suggestions.clone()
+            }
+            _ => Vec::new(),
+        };
+        let error = into_error(self, diagnostics);
+
+        if let Some(help) = styled_help
+            && color.enables_color(io::stdout().is_terminal())
+        {
+            println!("{}", help.trim_end());
+        } else if format == ErrorFormat::Json && !error.is_display() {
+            eprintln!("{}", json);
+        } else {
+            error.print(&self.styles, color, &suggestions);
+        }
+
+        self.exit_code_of(&error)
+    }
+
+    /// Attempts to return a [`CommandParsed`] from a single command line, like the ones typed in
+    /// a chat bot or an in-app console.
+    ///
+    /// The line is split into args with [`split_line()`], following POSIX shell quoting rules,
+    /// and parsed like [`App::try_parse_from()`]. The line starts with the command, not the binary
+    /// name, and flag indexes count the binary name as `0` unless [`App::no_binary_name()`] is set.
+    ///
+    /// # Errors:
+    /// Returns [`AppError::InvalidInput`] if the line has an unterminated quote, otherwise same
+    /// as [`App::try_parse_from()`].
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Bot").command(
+    ///     Command::new("deploy").subcommand(
+    ///         Command::new("web").flag(Flag::new("env").takes_value(true)),
+    ///     ),
+    /// );
+    ///
+    /// let cli = app.try_parse_line(r#"deploy web --env "staging eu""#).unwrap();
+    ///
+    /// assert_eq!(cli.get_subcommand(), Some("web"));
+    /// assert_eq!(cli.get_flag_value("env"), Some("staging eu"));
+    /// ```
+    pub fn try_parse_line(&self, line: &str) -> Result<CommandParsed, AppError> {
+        let mut args = split_line(line)?;
+
+        if !self.no_binary_name {
+            args.insert(0, self.name.clone());
         }
+
+        self.try_parse_from(args)
     }
 
     /// Returns a [`CommandParsed`] containing user input broken into strings to simpler use.
     /// This function automatically uses std::env::args_os() as the user input.
-    /// If you want to handle user input manually use [`App::parse()`] or [`App::try_parse()`].
+    /// If you want to handle user input manually use [`App::parse_from()`] or [`App::try_parse_from()`].
     /// If you don't want to handle errors manually use [`App::try_run()`].
     ///
     /// # Errors:
@@ -236,10 +785,10 @@ impl App {
     /// See the [`AppError`] struct for more info.
     ///
     /// # Example:
-    /// ```
+    /// ```rust,no_run
     /// use ecp::builder::*;
     ///
-    /// let cli = App::new("Rust")
+    /// let app = App::new("Rust")
     ///     .command(
     ///         Command::new("cargo")
     ///             .description("Rust's package manager")
@@ -270,7 +819,7 @@ impl App {
     ///                     ),
     ///             ),
     ///     )
-    ///     .try_parse_args(args);
+    ///     .run();
     ///      
     /// assert_eq!(app.get_command(), "cargo");
     /// assert_eq!(app.get_subcommand(), Some("run"));
@@ -280,12 +829,7 @@ impl App {
     /// assert_eq!(app.get_values().any(|f| f == "8080"), true);
     /// ```
     pub fn run(&self) -> CommandParsed {
-        let args: Vec<ffi::OsString> = env::args_os().collect();
-
-        match self.try_parse_args(args) {
-            Ok(parsed) => parsed,
-            Err(e) => e.exit(),
-        }
+        self.parse_from(env::args_os())
     }
     /// Returns a [`CommandParsed`] containing user input broken into strings to simpler use.
     /// This function automatically uses std::env::args_os() as the user input.
@@ -296,7 +840,7 @@ impl App {
     /// See the [`AppError`] struct for more info.
     ///
     /// # Example:
-    /// ```
+    /// ```rust,no_run
     /// use ecp::builder::*;
     ///
     /// let cli = App::new("Rust")
@@ -330,7 +874,7 @@ impl App {
     ///                     ),
     ///             ),
     ///     )
-    ///     .try_parse_args(args);
+    ///     .try_run();
     ///      
     /// let app = match cli {
     ///     Ok(parsed) => parsed,
@@ -347,12 +891,88 @@ impl App {
     /// assert_eq!(app.get_values().any(|f| f == "port"), true);
     /// assert_eq!(app.get_values().any(|f| f == "8080"), true);
     /// ```
-
     pub fn try_run(&self) -> Result<CommandParsed, AppError> {
-        let args: Vec<ffi::OsString> = env::args_os().collect();
+        self.try_parse_from(env::args_os())
+    }
+    /// Prints the error of `result`, if any, and returns the exit code set for it with
+    /// [`App::exit_code()`], so `main` can return it and destructors still run.
+    ///
+    /// Help and version output goes to stdout and exits with `0` by default, errors go to stderr
+    /// in the [`App::error_format()`] and [`App::color()`] of the app. Errors have no token or
+    /// position here, and the built-in `--error-format` and `--color` flags don't reach them, use
+    /// [`App::report_diagnostics()`] to keep both.
+    ///
+    /// # Example:
+    /// ```rust,no_run
+    /// use ecp::builder::*;
+    /// use std::process::ExitCode;
+    ///
+    /// fn main() -> ExitCode {
+    ///     let app = App::new("Rust").command(Command::new("cargo"));
+    ///
+    ///     app.report(app.try_run().map(|cli| println!("{}", cli.get_command())))
+    /// }
+    /// ```
+    pub fn report<T>(&self, result: Result<T, AppError>) -> ExitCode {
+        match result {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(e) => {
+                if self.error_format == ErrorFormat::Json && !e.is_display() {
+                    eprintln!("{}", e.to_json());
+                } else {
+                    e.print(&self.styles, self.color, &[]);
+                }
+
+                ExitCode::from(self.exit_code_of(&e))
+            }
+        }
+    }
+
+    /// Same as [`App::report()`], for the result of [`App::try_parse_diagnostics_from()`] or
+    /// [`App::try_dispatch_diagnostics_from()`].
+    ///
+    /// Errors are printed in the format and color picked for their parse, with the built-in
+    /// `--error-format` and `--color` flags, and JSON errors keep their token, position and
+    /// suggestions.
+    ///
+    /// # Example:
+    /// ```rust,no_run
+    /// use ecp::builder::*;
+    /// use ecp::dispatch::Context;
+    /// use std::process::ExitCode;
+    ///
+    /// fn main() -> ExitCode {
+    ///     let app = App::new("Rust").command(Command::new("cargo").handler(|_, _| Ok(())));
+    ///
+    ///     app.report_diagnostics(
+    ///         app.try_dispatch_diagnostics_from(std::env::args_os(), &mut Context::new()),
+    ///     )
+    /// }
+    /// ```
+    pub fn report_diagnostics<T>(&self, result: Result<T, Vec<Diagnostic>>) -> ExitCode {
+        match result {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(diagnostics) => ExitCode::from(self.print_diagnostics(diagnostics)),
+        }
+    }
 
-        self.try_parse_args(args)
+    /// Returns the exit code of `error`, [`AppError::Multiple`] uses the code of its first error
+    /// unless one is set for [`ErrorKind::Multiple`].
+    fn exit_code_of(&self, error: &AppError) -> u8 {
+        match error {
+            AppError::Multiple(errors)
+                if !errors.is_empty()
+                    && !self
+                        .exit_codes
+                        .iter()
+                        .any(|(k, _)| *k == ErrorKind::Multiple) =>
+            {
+                self.exit_code_of(&errors[0])
+            }
+            error => self.get_exit_code(error.kind()),
+        }
     }
+
     /// Returns the app name.
     pub fn get_name(&self) -> &str {
         &self.name
@@ -370,6 +990,94 @@ impl App {
         self.description.as_deref()
     }
 
+    /// Returns `true` if unambiguous prefixes are accepted.
+    pub fn allows_prefixes(&self) -> bool {
+        self.allow_prefixes
+    }
+
+    /// Returns `true` if unknown flags are collected instead of returning an error.
+    pub fn allows_unknown(&self) -> bool {
+        self.allow_unknown
+    }
+
+    /// Returns `true` if the first arg is the command instead of the binary name.
+    pub fn is_no_binary_name_set(&self) -> bool {
+        self.no_binary_name
+    }
+
+    /// Returns the exit code of errors of `kind`, see [`App::exit_code()`].
+    pub fn get_exit_code(&self, kind: ErrorKind) -> u8 {
+        self.exit_codes
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map_or(kind.default_exit_code(), |(_, code)| *code)
+    }
+
+    /// Returns how errors are printed, unless a parse picks another format.
+    pub fn get_error_format(&self) -> ErrorFormat {
+        self.error_format
+    }
+
+    /// Returns the styles of help and error output.
+    pub fn get_styles(&self) -> &Styles {
+        &self.styles
+    }
+
+    /// Returns when help and error output is styled, unless a parse picks another choice.
+    pub fn get_color(&self) -> ColorChoice {
+        self.color
+    }
+
+    /// Returns an iterator over the examples, as `(invocation, description)`.
+    pub fn get_examples(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.examples.iter().map(|(i, d)| (i.as_str(), d.as_str()))
+    }
+
+    /// Returns an iterator over the custom sections, as `(heading, body)`.
+    pub fn get_sections(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sections.iter().map(|(h, b)| (h.as_str(), b.as_str()))
+    }
+
+    /// Returns the template that lays out the help, if one was set.
+    pub fn get_help_template(&self) -> Option<&str> {
+        self.help_template.as_deref()
+    }
+
+    /// Returns the name of the built-in flag that opens the unstable gate, without `--`.
+    pub fn get_unstable_flag(&self) -> Option<&str> {
+        self.unstable_flag.as_deref()
+    }
+
+    /// Returns the environment variable that opens the unstable gate.
+    pub fn get_unstable_env(&self) -> Option<&str> {
+        self.unstable_env.as_deref()
+    }
+
+    /// Returns `true` if the environment variable of [`App::unstable_env()`] opens the gate.
+    pub(crate) fn is_unstable_env_set(&self) -> bool {
+        self.unstable_env
+            .as_ref()
+            .and_then(env::var_os)
+            .is_some_and(|value| !value.is_empty() && value != "0")
+    }
+
+    /// Returns the width help is wrapped to, if set with [`App::term_width()`].
+    pub fn get_term_width(&self) -> Option<usize> {
+        self.term_width
+    }
+
+    /// Returns the width help is wrapped to: [`App::term_width()`], `$COLUMNS` or 80.
+    pub(crate) fn help_width(&self) -> usize {
+        self.term_width
+            .or_else(|| env::var("COLUMNS").ok()?.trim().parse().ok())
+            .unwrap_or(DEFAULT_TERM_WIDTH)
+    }
+
+    /// Returns `true` if every recoverable error is returned at once.
+    pub fn is_collect_errors_set(&self) -> bool {
+        self.collect_errors
+    }
+
     /// Returns an iterator over the commands in the app.
     pub fn get_commands(&self) -> impl Iterator<Item = &Command> {
         self.commands.iter()
@@ -1,11 +1,30 @@
+use std::sync::Arc;
+
 use crate::builder::flag::Flag;
+use crate::dispatch::{BoxFuture, Context, ErrorHook, Handler, Hook, Hooks};
+use crate::errors::AppError;
+use crate::parser::CommandParsed;
 
 #[derive(Debug, Clone)]
 pub struct Command {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
+    pub(crate) long_description: Option<String>,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) visible_aliases: Vec<String>,
     pub(crate) subcommands: Vec<Command>,
     pub(crate) flags: Vec<Flag>,
+    pub(crate) allow_hyphen_values: bool,
+    pub(crate) handler: Option<Handler>,
+    pub(crate) hooks: Hooks,
+    pub(crate) examples: Vec<(String, String)>,
+    pub(crate) sections: Vec<(String, String)>,
+    pub(crate) help_template: Option<String>,
+    pub(crate) hidden: bool,
+    pub(crate) deprecated: Option<String>,
+    pub(crate) experimental: bool,
+    pub(crate) help_heading: Option<String>,
+    pub(crate) display_order: Option<usize>,
 }
 
 impl Command {
@@ -13,8 +32,22 @@ impl Command {
         Command {
             name: name.to_string(),
             description: None,
+            long_description: None,
+            aliases: Vec::new(),
+            visible_aliases: Vec::new(),
             subcommands: Vec::new(),
             flags: Vec::new(),
+            allow_hyphen_values: false,
+            handler: None,
+            hooks: Hooks::default(),
+            examples: Vec::new(),
+            sections: Vec::new(),
+            help_template: None,
+            hidden: false,
+            deprecated: None,
+            experimental: false,
+            help_heading: None,
+            display_order: None,
         }
     }
 
@@ -23,6 +56,25 @@ impl Command {
         self
     }
 
+    /// Sets the description shown in `--help`, man pages and Markdown, `-h` shows the
+    /// [`Command::description()`]. Without one, every help shows the description.
+    pub fn long_description(mut self, description: &str) -> Command {
+        self.long_description = Some(description.to_string());
+        self
+    }
+
+    /// Adds a hidden alias, the command can be called by it but it isn't shown to the user.
+    pub fn alias(mut self, alias: &str) -> Command {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    /// Adds an alias that is shown to the user alongside the command name.
+    pub fn visible_alias(mut self, alias: &str) -> Command {
+        self.visible_aliases.push(alias.to_string());
+        self
+    }
+
     pub fn subcommand(mut self, subcommand: Command) -> Command {
         self.subcommands.push(subcommand);
         self
@@ -33,6 +85,133 @@ impl Command {
         self
     }
 
+    /// Hides the command from help, man pages, Markdown, completions and typo suggestions, it's
+    /// still accepted.
+    pub fn hidden(mut self, hidden: bool) -> Command {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Marks the command as deprecated, `note` usually names the replacement, like
+    /// ``"use `new-name` instead"``.
+    ///
+    /// The command is still accepted, using it adds a warning to
+    /// [`CommandParsed::get_warnings()`].
+    pub fn deprecated(mut self, note: &str) -> Command {
+        self.deprecated = Some(note.to_string());
+        self
+    }
+
+    /// Marks the command as experimental, it's only accepted when the unstable gate is open, see
+    /// [`App::unstable_flag()`](crate::builder::App::unstable_flag).
+    pub fn experimental(mut self, experimental: bool) -> Command {
+        self.experimental = experimental;
+        self
+    }
+
+    /// Shows the command under its own heading in help, like "Plumbing commands", instead of
+    /// `Commands:` or `Subcommands:`. Headings are shown after those in the order they first
+    /// appear.
+    pub fn help_heading(mut self, heading: &str) -> Command {
+        self.help_heading = Some(heading.to_string());
+        self
+    }
+
+    /// Sets where the command is shown under its heading, lower first. Commands without one are
+    /// shown after, in definition order.
+    pub fn display_order(mut self, order: usize) -> Command {
+        self.display_order = Some(order);
+        self
+    }
+
+    /// Adds an example invocation, shown in help, man pages and Markdown with its description.
+    pub fn example(mut self, invocation: &str, description: &str) -> Command {
+        self.examples
+            .push((invocation.to_string(), description.to_string()));
+        self
+    }
+
+    /// Adds a section shown after the examples, like `Environment` or `See also`.
+    pub fn section(mut self, heading: &str, body: &str) -> Command {
+        self.sections.push((heading.to_string(), body.to_string()));
+        self
+    }
+
+    /// Sets the layout of the help of the command, overriding [`App::help_template()`].
+    ///
+    /// [`App::help_template()`]: crate::builder::App::help_template
+    pub fn help_template(mut self, template: &str) -> Command {
+        self.help_template = Some(template.to_string());
+        self
+    }
+
+    /// Accepts values starting with `-` that don't match any flag, like `-x` in `proxy run -x`.
+    ///
+    /// A lone `-` and negative numbers, like `-3.2`, are always accepted as values, unless the
+    /// number matches a short flag.
+    pub fn allow_hyphen_values(mut self, allow: bool) -> Command {
+        self.allow_hyphen_values = allow;
+        self
+    }
+
+    /// Sets the handler called by [`App::try_dispatch_from()`](crate::builder::App::try_dispatch_from)
+    /// when this is the deepest command with a handler in the args.
+    ///
+    /// The handler receives the parsed args and the [`Context`] shared by the dispatch.
+    pub fn handler<F>(mut self, handler: F) -> Command
+    where
+        F: Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        self.handler = Some(Handler::Sync(Arc::new(handler)));
+        self
+    }
+
+    /// Sets an async handler, awaited by
+    /// [`App::try_dispatch_async_from()`](crate::builder::App::try_dispatch_async_from).
+    ///
+    /// The handler returns a boxed future that may borrow the parsed args and the [`Context`],
+    /// usually `Box::pin(async move { ... })`.
+    pub fn async_handler<F>(mut self, handler: F) -> Command
+    where
+        F: for<'a> Fn(&'a CommandParsed, &'a mut Context) -> BoxFuture<'a> + Send + Sync + 'static,
+    {
+        self.handler = Some(Handler::Async(Arc::new(handler)));
+        self
+    }
+
+    /// Adds a hook that runs before the handler when this command is in the args, after the hooks
+    /// of its parents. An error stops the dispatch before the handler runs.
+    pub fn before<F>(mut self, hook: F) -> Command
+    where
+        F: Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        self.hooks.before.push(Hook::new(hook));
+        self
+    }
+
+    /// Adds a hook that runs after the handler succeeds when this command is in the args, before
+    /// the hooks of its parents.
+    pub fn after<F>(mut self, hook: F) -> Command
+    where
+        F: Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        self.hooks.after.push(Hook::new(hook));
+        self
+    }
+
+    /// Adds a hook that receives the error of a hook or of the handler when this command is in the
+    /// args. Returning `Ok` handles the error, returning `Err` passes it on to the next hook.
+    pub fn on_error<F>(mut self, hook: F) -> Command
+    where
+        F: Fn(&CommandParsed, &mut Context, AppError) -> Result<(), AppError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.on_error.push(ErrorHook::new(hook));
+        self
+    }
+
     pub fn get_name(&self) -> &str {
         &self.name
     }
@@ -41,6 +220,61 @@ impl Command {
         self.description.as_deref()
     }
 
+    pub fn get_long_description(&self) -> Option<&str> {
+        self.long_description.as_deref()
+    }
+
+    /// Returns the description shown in the long or short help.
+    pub(crate) fn get_help_description(&self, long: bool) -> Option<&str> {
+        match &self.long_description {
+            Some(description) if long => Some(description),
+            _ => self.description.as_deref(),
+        }
+    }
+
+    pub fn allows_hyphen_values(&self) -> bool {
+        self.allow_hyphen_values
+    }
+
+    pub fn get_help_heading(&self) -> Option<&str> {
+        self.help_heading.as_deref()
+    }
+
+    pub fn get_display_order(&self) -> Option<usize> {
+        self.display_order
+    }
+
+    /// Returns an iterator over every alias, visible and hidden.
+    pub fn get_aliases(&self) -> impl Iterator<Item = &str> {
+        self.visible_aliases
+            .iter()
+            .chain(self.aliases.iter())
+            .map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over the aliases that are shown to the user.
+    pub fn get_visible_aliases(&self) -> impl Iterator<Item = &str> {
+        self.visible_aliases.iter().map(|s| s.as_str())
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Returns the deprecation note, if the command is deprecated.
+    pub fn get_deprecated(&self) -> Option<&str> {
+        self.deprecated.as_deref()
+    }
+
+    pub fn is_experimental(&self) -> bool {
+        self.experimental
+    }
+
+    /// Returns `true` if the command has a handler.
+    pub fn is_handler_set(&self) -> bool {
+        self.handler.is_some()
+    }
+
     pub fn get_subcommands(&self) -> &[Command] {
         &self.subcommands
     }
@@ -48,4 +282,23 @@ impl Command {
     pub fn get_flags(&self) -> &[Flag] {
         &self.flags
     }
+
+    /// Returns an iterator over the examples, as `(invocation, description)`.
+    pub fn get_examples(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.examples.iter().map(|(i, d)| (i.as_str(), d.as_str()))
+    }
+
+    /// Returns an iterator over the custom sections, as `(heading, body)`.
+    pub fn get_sections(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.sections.iter().map(|(h, b)| (h.as_str(), b.as_str()))
+    }
+
+    pub fn get_help_template(&self) -> Option<&str> {
+        self.help_template.as_deref()
+    }
+
+    /// Returns an iterator over the command name and every alias.
+    pub(crate) fn get_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.get_aliases())
+    }
 }
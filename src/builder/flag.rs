@@ -1,8 +1,40 @@
+/// What to do when a [`Flag::key_value()`] flag gets the same key more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Return an [`AppError::DuplicateKey`](crate::errors::AppError::DuplicateKey).
+    Error,
+    /// Keep only the last value.
+    LastWins,
+    /// Keep every value, in order.
+    Collect,
+}
+
 #[derive(Debug, Clone)]
 pub struct Flag {
     pub(crate) long: String,
     pub(crate) short: Option<char>,
     pub(crate) description: Option<String>,
+    pub(crate) long_description: Option<String>,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) visible_aliases: Vec<String>,
+    pub(crate) short_aliases: Vec<char>,
+    pub(crate) visible_short_aliases: Vec<char>,
+    pub(crate) negatable: bool,
+    pub(crate) enabled_by_default: bool,
+    pub(crate) takes_value: bool,
+    pub(crate) value_delimiter: Option<char>,
+    pub(crate) min_occurrences: usize,
+    pub(crate) max_occurrences: Option<usize>,
+    pub(crate) key_value: bool,
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) optional_value: bool,
+    pub(crate) default_missing_value: Option<String>,
+    pub(crate) allow_hyphen_values: bool,
+    pub(crate) hidden: bool,
+    pub(crate) deprecated: Option<String>,
+    pub(crate) experimental: bool,
+    pub(crate) help_heading: Option<String>,
+    pub(crate) display_order: Option<usize>,
 }
 
 impl Flag {
@@ -11,6 +43,27 @@ impl Flag {
             long: long.to_string(),
             short: None,
             description: None,
+            long_description: None,
+            aliases: Vec::new(),
+            visible_aliases: Vec::new(),
+            short_aliases: Vec::new(),
+            visible_short_aliases: Vec::new(),
+            negatable: false,
+            enabled_by_default: false,
+            takes_value: false,
+            value_delimiter: None,
+            min_occurrences: 0,
+            max_occurrences: None,
+            key_value: false,
+            duplicate_keys: DuplicateKeys::LastWins,
+            optional_value: false,
+            default_missing_value: None,
+            allow_hyphen_values: false,
+            hidden: false,
+            deprecated: None,
+            experimental: false,
+            help_heading: None,
+            display_order: None,
         }
     }
 
@@ -19,11 +72,183 @@ impl Flag {
         self
     }
 
+    /// Sets the description shown in `--help`, man pages and Markdown, `-h` shows the
+    /// [`Flag::description()`]. Without one, every help shows the description.
+    pub fn long_description(mut self, description: &str) -> Flag {
+        self.long_description = Some(description.to_string());
+        self
+    }
+
     pub fn short(mut self, short: char) -> Flag {
         self.short = Some(short);
         self
     }
 
+    /// Adds a hidden long alias, the flag can be called by it but it isn't shown to the user.
+    pub fn alias(mut self, alias: &str) -> Flag {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    /// Adds a long alias that is shown to the user alongside the flag name.
+    pub fn visible_alias(mut self, alias: &str) -> Flag {
+        self.visible_aliases.push(alias.to_string());
+        self
+    }
+
+    /// Adds a hidden short alias.
+    pub fn short_alias(mut self, alias: char) -> Flag {
+        self.short_aliases.push(alias);
+        self
+    }
+
+    /// Adds a short alias that is shown to the user alongside the short flag.
+    pub fn visible_short_alias(mut self, alias: char) -> Flag {
+        self.visible_short_aliases.push(alias);
+        self
+    }
+
+    /// Accepts `--no-<long>` (and `--no-<alias>`) to turn the flag off.
+    ///
+    /// When the flag is given more than once the last occurrence wins, so `--color --no-color`
+    /// leaves it off. The final state is returned by
+    /// [`CommandParsed::get_flag_state()`](crate::parser::CommandParsed::get_flag_state).
+    pub fn negatable(mut self, negatable: bool) -> Flag {
+        self.negatable = negatable;
+        self
+    }
+
+    /// Sets the state of the flag when it isn't given, use it with [`Flag::negatable()`] for
+    /// flags that are on by default.
+    pub fn enabled_by_default(mut self, enabled: bool) -> Flag {
+        self.enabled_by_default = enabled;
+        self
+    }
+
+    /// Makes the flag take a value, given as `--long value`, `--long=value`, `-s value`,
+    /// `-s=value` or `-svalue`.
+    ///
+    /// The flag can occur more than once, every value is kept in order and can be read with
+    /// [`CommandParsed::get_flag_values()`](crate::parser::CommandParsed::get_flag_values).
+    pub fn takes_value(mut self, takes_value: bool) -> Flag {
+        self.takes_value = takes_value;
+        self
+    }
+
+    /// Splits every value of the flag at `delimiter`, so `--tags a,b,c` gives three values.
+    ///
+    /// Implies [`Flag::takes_value()`].
+    pub fn value_delimiter(mut self, delimiter: char) -> Flag {
+        self.takes_value = true;
+        self.value_delimiter = Some(delimiter);
+        self
+    }
+
+    /// Sets how many times the flag must occur at least, `1` makes the flag required.
+    pub fn min_occurrences(mut self, min: usize) -> Flag {
+        self.min_occurrences = min;
+        self
+    }
+
+    /// Sets how many times the flag can occur at most.
+    pub fn max_occurrences(mut self, max: usize) -> Flag {
+        self.max_occurrences = Some(max);
+        self
+    }
+
+    /// Makes the value of the flag optional, like `--color` and `--color=always`.
+    ///
+    /// The value can only be attached with `=`, as `--long=value` or `-s=value`, so the next arg
+    /// is never taken as the flag value and `-sv` sets `-s` and `-v`. See
+    /// [`Flag::default_missing_value()`] for the value used when the flag is given without one.
+    ///
+    /// Implies [`Flag::takes_value()`].
+    pub fn optional_value(mut self, optional: bool) -> Flag {
+        self.takes_value = self.takes_value || optional;
+        self.optional_value = optional;
+        self
+    }
+
+    /// Sets the value of the flag when it's given without one, `--log` is read as `--log=value`.
+    ///
+    /// Implies [`Flag::optional_value()`].
+    pub fn default_missing_value(mut self, value: &str) -> Flag {
+        self.takes_value = true;
+        self.optional_value = true;
+        self.default_missing_value = Some(value.to_string());
+        self
+    }
+
+    /// Accepts values starting with `-`, like `--args -x`.
+    ///
+    /// Negative numbers, like `--offset -5`, are always accepted as values unless they match a
+    /// short flag.
+    pub fn allow_hyphen_values(mut self, allow: bool) -> Flag {
+        self.allow_hyphen_values = allow;
+        self
+    }
+
+    /// Makes every value of the flag a `key=value` pair, like `-D key=value` or `--set a.b=c`.
+    ///
+    /// Values are split at the first `=`, keys can't be empty and can only contain alphanumeric
+    /// characters, `.`, `_` and `-`. The pairs are collected into a
+    /// [`PropertyMap`](crate::parser::PropertyMap), see
+    /// [`CommandParsed::get_properties()`](crate::parser::CommandParsed::get_properties).
+    /// [`App::validate()`](crate::builder::App::validate) rejects `=` as
+    /// [`Flag::value_delimiter()`] of a key-value flag.
+    ///
+    /// Implies [`Flag::takes_value()`].
+    pub fn key_value(mut self, key_value: bool) -> Flag {
+        self.takes_value = self.takes_value || key_value;
+        self.key_value = key_value;
+        self
+    }
+
+    /// Sets what happens when a [`Flag::key_value()`] flag gets the same key more than once,
+    /// the default is [`DuplicateKeys::LastWins`].
+    pub fn duplicate_keys(mut self, policy: DuplicateKeys) -> Flag {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Hides the flag from help, man pages, Markdown, completions and typo suggestions, it's still
+    /// accepted, like an internal debugging option.
+    pub fn hidden(mut self, hidden: bool) -> Flag {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Marks the flag as deprecated, `note` usually names the replacement, like
+    /// ``"use `--new-name` instead"``.
+    ///
+    /// The flag is still accepted, using it adds a warning to
+    /// [`CommandParsed::get_warnings()`](crate::parser::CommandParsed::get_warnings).
+    pub fn deprecated(mut self, note: &str) -> Flag {
+        self.deprecated = Some(note.to_string());
+        self
+    }
+
+    /// Marks the flag as experimental, it's only accepted when the unstable gate is open, see
+    /// [`App::unstable_flag()`](crate::builder::App::unstable_flag).
+    pub fn experimental(mut self, experimental: bool) -> Flag {
+        self.experimental = experimental;
+        self
+    }
+
+    /// Shows the flag under its own heading in help, like "Output options", instead of
+    /// `Flags:`. Headings are shown after `Flags:` in the order they first appear.
+    pub fn help_heading(mut self, heading: &str) -> Flag {
+        self.help_heading = Some(heading.to_string());
+        self
+    }
+
+    /// Sets where the flag is shown under its heading, lower first. Flags without one are
+    /// shown after, in definition order.
+    pub fn display_order(mut self, order: usize) -> Flag {
+        self.display_order = Some(order);
+        self
+    }
+
     pub fn get_long(&self) -> &str {
         &self.long
     }
@@ -35,4 +260,117 @@ impl Flag {
     pub fn get_description(&self) -> Option<&str> {
         self.description.as_deref()
     }
+
+    pub fn get_long_description(&self) -> Option<&str> {
+        self.long_description.as_deref()
+    }
+
+    /// Returns the description shown in the long or short help.
+    pub(crate) fn get_help_description(&self, long: bool) -> Option<&str> {
+        match &self.long_description {
+            Some(description) if long => Some(description),
+            _ => self.description.as_deref(),
+        }
+    }
+
+    pub fn is_negatable(&self) -> bool {
+        self.negatable
+    }
+
+    pub fn is_enabled_by_default(&self) -> bool {
+        self.enabled_by_default
+    }
+
+    pub fn is_takes_value_set(&self) -> bool {
+        self.takes_value
+    }
+
+    pub fn get_value_delimiter(&self) -> Option<char> {
+        self.value_delimiter
+    }
+
+    pub fn get_min_occurrences(&self) -> usize {
+        self.min_occurrences
+    }
+
+    pub fn get_max_occurrences(&self) -> Option<usize> {
+        self.max_occurrences
+    }
+
+    pub fn is_key_value(&self) -> bool {
+        self.key_value
+    }
+
+    pub fn get_duplicate_keys(&self) -> DuplicateKeys {
+        self.duplicate_keys
+    }
+
+    pub fn is_optional_value(&self) -> bool {
+        self.optional_value
+    }
+
+    pub fn get_default_missing_value(&self) -> Option<&str> {
+        self.default_missing_value.as_deref()
+    }
+
+    pub fn allows_hyphen_values(&self) -> bool {
+        self.allow_hyphen_values
+    }
+
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+
+    pub fn get_help_heading(&self) -> Option<&str> {
+        self.help_heading.as_deref()
+    }
+
+    pub fn get_display_order(&self) -> Option<usize> {
+        self.display_order
+    }
+
+    /// Returns the deprecation note, if the flag is deprecated.
+    pub fn get_deprecated(&self) -> Option<&str> {
+        self.deprecated.as_deref()
+    }
+
+    pub fn is_experimental(&self) -> bool {
+        self.experimental
+    }
+
+    /// Returns an iterator over every long alias, visible and hidden.
+    pub fn get_aliases(&self) -> impl Iterator<Item = &str> {
+        self.visible_aliases
+            .iter()
+            .chain(self.aliases.iter())
+            .map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over the long aliases that are shown to the user.
+    pub fn get_visible_aliases(&self) -> impl Iterator<Item = &str> {
+        self.visible_aliases.iter().map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over every short alias, visible and hidden.
+    pub fn get_short_aliases(&self) -> impl Iterator<Item = char> + '_ {
+        self.visible_short_aliases
+            .iter()
+            .chain(self.short_aliases.iter())
+            .copied()
+    }
+
+    /// Returns an iterator over the short aliases that are shown to the user.
+    pub fn get_visible_short_aliases(&self) -> impl Iterator<Item = char> + '_ {
+        self.visible_short_aliases.iter().copied()
+    }
+
+    /// Returns an iterator over the long name and every long alias.
+    pub(crate) fn get_long_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.long.as_str()).chain(self.get_aliases())
+    }
+
+    /// Returns an iterator over the short flag and every short alias.
+    pub(crate) fn get_short_names(&self) -> impl Iterator<Item = char> + '_ {
+        self.short.into_iter().chain(self.get_short_aliases())
+    }
 }
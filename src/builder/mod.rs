@@ -1,7 +1,8 @@
 mod command;
 mod flag;
 mod app;
+mod validate;
 
 pub use command::Command;
-pub use flag::Flag;
+pub use flag::{DuplicateKeys, Flag};
 pub use app::App;
\ No newline at end of file
@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+
+use crate::builder::{App, Command};
+use crate::errors::ValidationProblem;
+
+/// Walks the whole [`App`] tree and returns every problem found, in definition order.
+pub(super) fn problems(app: &App) -> Vec<ValidationProblem> {
+    let mut problems = Vec::new();
+
+    check_commands(&app.name, &app.commands, &mut problems);
+
+    problems
+}
+
+fn check_commands(scope: &str, commands: &[Command], problems: &mut Vec<ValidationProblem>) {
+    let mut names = HashSet::new();
+
+    for cmd in commands {
+        for name in cmd.get_names() {
+            if !is_valid_name(name) {
+                problems.push(ValidationProblem::InvalidCommandName {
+                    scope: scope.to_string(),
+                    name: name.to_string(),
+                });
+            }
+
+            if !names.insert(name) {
+                problems.push(ValidationProblem::DuplicateCommand {
+                    scope: scope.to_string(),
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        let cmd_scope = format!("{} {}", scope, cmd.name);
+        check_flags(&cmd_scope, cmd, problems);
+        check_commands(&cmd_scope, &cmd.subcommands, problems);
+    }
+}
+
+fn check_flags(scope: &str, cmd: &Command, problems: &mut Vec<ValidationProblem>) {
+    let mut longs = HashSet::new();
+    let mut shorts = HashSet::new();
+
+    for flag in &cmd.flags {
+        for long in flag.get_long_names() {
+            if !is_valid_name(long) || long.contains('=') {
+                problems.push(ValidationProblem::InvalidFlagName {
+                    scope: scope.to_string(),
+                    long: long.to_string(),
+                });
+            }
+
+            if !longs.insert(long) {
+                problems.push(ValidationProblem::DuplicateFlag {
+                    scope: scope.to_string(),
+                    long: long.to_string(),
+                });
+            }
+        }
+
+        if flag
+            .max_occurrences
+            .is_some_and(|max| max < flag.min_occurrences)
+        {
+            problems.push(ValidationProblem::InvalidOccurrences {
+                scope: scope.to_string(),
+                long: flag.long.clone(),
+            });
+        }
+
+        // The pairs of a key-value flag are split at `=` after the delimiter
+        if flag.key_value && flag.value_delimiter == Some('=') {
+            problems.push(ValidationProblem::InvalidDelimiter {
+                scope: scope.to_string(),
+                long: flag.long.clone(),
+            });
+        }
+
+        for short in flag.get_short_names() {
+            if !is_valid_short(short) {
+                problems.push(ValidationProblem::InvalidShort {
+                    scope: scope.to_string(),
+                    short,
+                });
+            }
+
+            if !shorts.insert(short) {
+                problems.push(ValidationProblem::ShortCollision {
+                    scope: scope.to_string(),
+                    short,
+                });
+            }
+        }
+    }
+
+    // `--no-<long>` of negatable flags can't shadow another flag
+    for flag in cmd.flags.iter().filter(|flag| flag.negatable) {
+        for long in flag.get_long_names() {
+            let negated = format!("no-{}", long);
+
+            if longs.contains(negated.as_str()) {
+                problems.push(ValidationProblem::DuplicateFlag {
+                    scope: scope.to_string(),
+                    long: negated,
+                });
+            }
+        }
+    }
+}
+
+/// Names must be non-empty, can't start with `-` and can't contain whitespace.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('-') && !name.chars().any(char::is_whitespace)
+}
+
+fn is_valid_short(short: char) -> bool {
+    short != '-' && short != '=' && !short.is_whitespace() && !short.is_control()
+}
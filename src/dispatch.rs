@@ -0,0 +1,516 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::ffi;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::{env, fmt};
+
+use crate::builder::{App, Command};
+use crate::errors::{AppError, Diagnostic};
+use crate::help::render_path;
+use crate::parser::CommandParsed;
+use crate::parser::utils::into_error;
+use crate::style::{ColorChoice, Styles};
+
+/// Values shared by every handler during a dispatch, stored by type.
+///
+/// Values must be [`Send`], so async handlers can hold the context across an `.await` on a
+/// multi-threaded executor.
+///
+/// # Example:
+/// ```
+/// use ecp::dispatch::Context;
+///
+/// struct Verbose(bool);
+///
+/// let mut ctx = Context::new();
+/// ctx.insert(Verbose(true));
+/// ctx.insert(3_u8);
+///
+/// assert!(ctx.get::<Verbose>().unwrap().0);
+/// assert_eq!(ctx.get::<u8>(), Some(&3));
+/// assert_eq!(ctx.get::<u16>(), None);
+/// ```
+#[derive(Default)]
+pub struct Context {
+    values: HashMap<TypeId, Box<dyn Any + Send>>,
+}
+
+impl Context {
+    pub fn new() -> Context {
+        Context::default()
+    }
+
+    /// Inserts `value`, returning the previous value of the same type.
+    pub fn insert<T: Any + Send>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref())
+    }
+
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.values
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_mut())
+    }
+
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+}
+
+impl fmt::Debug for Context {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Context")
+            .field("len", &self.values.len())
+            .finish()
+    }
+}
+
+type HandlerFn = dyn Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync;
+type AsyncHandlerFn =
+    dyn for<'a> Fn(&'a CommandParsed, &'a mut Context) -> BoxFuture<'a> + Send + Sync;
+type ErrorHookFn =
+    dyn Fn(&CommandParsed, &mut Context, AppError) -> Result<(), AppError> + Send + Sync;
+
+/// The future returned by an async handler, see [`Command::async_handler()`].
+pub type BoxFuture<'a> = Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + 'a>>;
+
+/// A handler attached with [`Command::handler()`] or [`Command::async_handler()`].
+///
+/// Handlers are [`Arc`]s of `Send + Sync` closures, so an [`App`] can be shared between threads.
+#[derive(Clone)]
+pub(crate) enum Handler {
+    Sync(Arc<HandlerFn>),
+    Async(Arc<AsyncHandlerFn>),
+}
+
+impl fmt::Debug for Handler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Handler::Sync(_) => f.write_str("Handler::Sync"),
+            Handler::Async(_) => f.write_str("Handler::Async"),
+        }
+    }
+}
+
+/// A before or after hook.
+#[derive(Clone)]
+pub(crate) struct Hook(Arc<HandlerFn>);
+
+impl Hook {
+    pub(crate) fn new<F>(hook: F) -> Hook
+    where
+        F: Fn(&CommandParsed, &mut Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        Hook(Arc::new(hook))
+    }
+}
+
+impl fmt::Debug for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Hook")
+    }
+}
+
+/// A hook attached with [`App::on_error()`] or [`Command::on_error()`].
+#[derive(Clone)]
+pub(crate) struct ErrorHook(Arc<ErrorHookFn>);
+
+impl ErrorHook {
+    pub(crate) fn new<F>(hook: F) -> ErrorHook
+    where
+        F: Fn(&CommandParsed, &mut Context, AppError) -> Result<(), AppError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        ErrorHook(Arc::new(hook))
+    }
+}
+
+impl fmt::Debug for ErrorHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ErrorHook")
+    }
+}
+
+/// The lifecycle hooks of an [`App`] or a [`Command`], each in the order they were added.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hooks {
+    pub(crate) before: Vec<Hook>,
+    pub(crate) after: Vec<Hook>,
+    pub(crate) on_error: Vec<ErrorHook>,
+}
+
+impl App {
+    /// Parses `args` like [`App::try_parse_from()`] and calls the handler of the deepest command
+    /// found, returning its result.
+    ///
+    /// Commands without a handler fall back to the handler of their parent. If no command in the
+    /// path has a handler, [`AppError::DisplayHelp`] is returned with the help of the deepest
+    /// command, like for `--help`.
+    ///
+    /// The hooks of the app and of every command in the path run around the handler:
+    /// 1. `before` hooks, from the app to the deepest command.
+    /// 2. The handler.
+    /// 3. `after` hooks, from the deepest command to the app.
+    ///
+    /// If any of them fails, the `on_error` hooks run from the deepest command to the app, each
+    /// receiving the error returned by the previous one, until one of them returns `Ok`. No hook
+    /// runs if the args can't be parsed or no handler is found.
+    ///
+    /// Like [`App::parse_from()`], the warnings of the deprecated commands and flags used are
+    /// printed to stderr before the hooks run.
+    ///
+    /// # Errors:
+    /// Returns the parse error, or the error left by the `on_error` hooks.
+    /// Returns [`AppError::AsyncHandler`] if the handler found is async, use
+    /// [`App::try_dispatch_async_from()`] for those.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::dispatch::Context;
+    ///
+    /// struct Built(Vec<String>);
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo")
+    ///         .subcommand(Command::new("build").handler(|cli, ctx| {
+    ///             let values = cli.get_values().map(|v| v.to_string()).collect();
+    ///             ctx.insert(Built(values));
+    ///             Ok(())
+    ///         })),
+    /// );
+    ///
+    /// let mut ctx = Context::new();
+    /// app.try_dispatch_from(["rust", "cargo", "build", "ecp"], &mut ctx).unwrap();
+    ///
+    /// assert_eq!(ctx.get::<Built>().unwrap().0, ["ecp"]);
+    /// ```
+    pub fn try_dispatch_from<I, T>(&self, args: I, ctx: &mut Context) -> Result<(), AppError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        let dispatch = self
+            .prepare_dispatch(args)
+            .map_err(|diagnostics| into_error(self, diagnostics))?;
+
+        dispatch.run(ctx)
+    }
+
+    /// Same as [`App::try_dispatch_from()`], but parse errors come as [`Diagnostic`]s, like
+    /// [`App::try_parse_diagnostics_from()`], so [`App::report_diagnostics()`] prints them in
+    /// the format and color picked with the built-in flags.
+    ///
+    /// The error left by the `on_error` hooks is returned as a single diagnostic without a
+    /// token.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::dispatch::Context;
+    /// use ecp::errors::ErrorFormat;
+    ///
+    /// let app = App::new("Rust").command(Command::new("cargo").handler(|_, _| Ok(())));
+    ///
+    /// let diagnostics = app
+    ///     .try_dispatch_diagnostics_from(
+    ///         ["rust", "--error-format=json", "cargo", "--bogus"],
+    ///         &mut Context::new(),
+    ///     )
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(diagnostics[0].get_token(), Some("--bogus"));
+    /// assert_eq!(diagnostics[0].get_error_format(), ErrorFormat::Json);
+    /// ```
+    pub fn try_dispatch_diagnostics_from<I, T>(
+        &self,
+        args: I,
+        ctx: &mut Context,
+    ) -> Result<(), Vec<Diagnostic>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        let dispatch = self.prepare_dispatch(args)?;
+
+        dispatch
+            .run(ctx)
+            .map_err(|error| vec![diagnostic(&dispatch.parsed, error)])
+    }
+
+    /// Dispatches [`std::env::args_os()`], see [`App::try_dispatch_from()`].
+    ///
+    /// # Errors:
+    /// Returns the parse error, or the error returned by the handler.
+    ///
+    /// # Example:
+    /// ```rust,no_run
+    /// use ecp::builder::*;
+    /// use ecp::dispatch::Context;
+    ///
+    /// let app = App::new("Rust").command(Command::new("cargo").handler(|cli, _| {
+    ///     println!("{:?}", cli.get_flags().collect::<Vec<_>>());
+    ///     Ok(())
+    /// }));
+    ///
+    /// if let Err(e) = app.dispatch(&mut Context::new()) {
+    ///     e.exit();
+    /// }
+    /// ```
+    pub fn dispatch(&self, ctx: &mut Context) -> Result<(), AppError> {
+        self.try_dispatch_from(env::args_os(), ctx)
+    }
+
+    /// Same as [`App::try_dispatch_from()`], but awaits the handler if it's async.
+    ///
+    /// Hooks and sync handlers are called as usual. This doesn't depend on any async runtime,
+    /// the future runs on whichever executor awaits it.
+    ///
+    /// # Errors:
+    /// Returns the parse error, or the error left by the `on_error` hooks.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use ecp::dispatch::Context;
+    ///
+    /// struct Status(String);
+    ///
+    /// async fn status(daemon: &str) -> String {
+    ///     format!("{} is up", daemon)
+    /// }
+    ///
+    /// let app = App::new("Ctl").command(Command::new("status").async_handler(|cli, ctx| {
+    ///     Box::pin(async move {
+    ///         let daemon = cli.get_values().next().unwrap_or("all");
+    ///         ctx.insert(Status(status(daemon).await));
+    ///         Ok(())
+    ///     })
+    /// }));
+    ///
+    /// let mut ctx = Context::new();
+    ///
+    /// // Any executor works, this future is ready on its first poll
+    /// {
+    ///     let future = std::pin::pin!(app.try_dispatch_async_from(["ctl", "status", "db"], &mut ctx));
+    ///     let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    ///     assert!(future.poll(&mut cx).is_ready());
+    /// }
+    ///
+    /// assert_eq!(ctx.get::<Status>().unwrap().0, "db is up");
+    /// ```
+    pub async fn try_dispatch_async_from<I, T>(
+        &self,
+        args: I,
+        ctx: &mut Context,
+    ) -> Result<(), AppError>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        let dispatch = self
+            .prepare_dispatch(args)
+            .map_err(|diagnostics| into_error(self, diagnostics))?;
+
+        dispatch.run_async(ctx).await
+    }
+
+    /// Same as [`App::try_dispatch_async_from()`], but errors come as [`Diagnostic`]s, see
+    /// [`App::try_dispatch_diagnostics_from()`].
+    pub async fn try_dispatch_async_diagnostics_from<I, T>(
+        &self,
+        args: I,
+        ctx: &mut Context,
+    ) -> Result<(), Vec<Diagnostic>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        let dispatch = self.prepare_dispatch(args)?;
+
+        dispatch
+            .run_async(ctx)
+            .await
+            .map_err(|error| vec![diagnostic(&dispatch.parsed, error)])
+    }
+
+    /// Dispatches [`std::env::args_os()`], see [`App::try_dispatch_async_from()`].
+    ///
+    /// # Errors:
+    /// Returns the parse error, or the error returned by the handler.
+    pub async fn dispatch_async(&self, ctx: &mut Context) -> Result<(), AppError> {
+        self.try_dispatch_async_from(env::args_os(), ctx).await
+    }
+
+    /// Parses `args` and finds the handler to call, with the hooks of its path.
+    fn prepare_dispatch<I, T>(&self, args: I) -> Result<Dispatch<'_>, Vec<Diagnostic>>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<ffi::OsString>,
+    {
+        let parsed = self.try_parse_diagnostics_from(args)?;
+        self.warn(&parsed);
+
+        let path = self.find_path(&parsed);
+
+        let Some(handler) = find_handler(&path) else {
+            let help = render_path(self, &path, &Styles::plain(), false);
+            let styled_help = (parsed.color != ColorChoice::Never)
+                .then(|| render_path(self, &path, &self.styles, false));
+
+            return Err(vec![Diagnostic {
+                styled_help,
+                ..diagnostic(&parsed, AppError::DisplayHelp(help))
+            }]);
+        };
+
+        let hooks = self.find_hooks(&path);
+
+        Ok(Dispatch {
+            parsed,
+            handler,
+            hooks,
+        })
+    }
+
+    /// Returns the commands of `parsed`, from the top-level command to the deepest subcommand.
+    pub(crate) fn find_path(&self, parsed: &CommandParsed) -> Vec<&Command> {
+        let mut commands = &self.commands;
+        let mut path = Vec::new();
+
+        for name in parsed.get_command_path() {
+            let Some(cmd) = commands.iter().find(|cmd| cmd.name == name) else {
+                break;
+            };
+
+            path.push(cmd);
+            commands = &cmd.subcommands;
+        }
+
+        path
+    }
+
+    /// Returns the hooks of the app, then of every command in `path`, outermost first.
+    fn find_hooks<'a>(&'a self, path: &[&'a Command]) -> Vec<&'a Hooks> {
+        std::iter::once(&self.hooks)
+            .chain(path.iter().map(|cmd| &cmd.hooks))
+            .collect()
+    }
+}
+
+/// A parsed command line, with the handler to call and the hooks around it.
+struct Dispatch<'a> {
+    parsed: CommandParsed,
+    handler: &'a Handler,
+    hooks: Vec<&'a Hooks>,
+}
+
+impl Dispatch<'_> {
+    /// Runs the hooks and the handler, which must be sync.
+    fn run(&self, ctx: &mut Context) -> Result<(), AppError> {
+        let parsed = &self.parsed;
+
+        let result = self.before(ctx).and_then(|()| match self.handler {
+            Handler::Sync(handler) => handler(parsed, ctx),
+            Handler::Async(_) => Err(AppError::AsyncHandler(parsed.path.join(" "))),
+        });
+
+        self.finish(ctx, result)
+    }
+
+    /// Runs the hooks and the handler, awaiting it if it's async.
+    async fn run_async(&self, ctx: &mut Context) -> Result<(), AppError> {
+        let parsed = &self.parsed;
+
+        let mut result = self.before(ctx);
+
+        if result.is_ok() {
+            result = match self.handler {
+                Handler::Sync(handler) => handler(parsed, ctx),
+                Handler::Async(handler) => handler(parsed, ctx).await,
+            };
+        }
+
+        self.finish(ctx, result)
+    }
+
+    fn before(&self, ctx: &mut Context) -> Result<(), AppError> {
+        run_before(&self.hooks, &self.parsed, ctx)
+    }
+
+    /// Runs the `after` hooks if the handler succeeded, then passes any error through the
+    /// `on_error` hooks.
+    fn finish(&self, ctx: &mut Context, result: Result<(), AppError>) -> Result<(), AppError> {
+        let result = result.and_then(|()| run_after(&self.hooks, &self.parsed, ctx));
+        run_on_error(&self.hooks, &self.parsed, ctx, result)
+    }
+}
+
+/// Returns a [`Diagnostic`] of an error found after `parsed`, printed in its format and color.
+fn diagnostic(parsed: &CommandParsed, error: AppError) -> Diagnostic {
+    Diagnostic {
+        error_format: parsed.error_format,
+        color: parsed.color,
+        ..Diagnostic::from(error)
+    }
+}
+
+/// Returns the handler of the deepest command in `path` that has one.
+fn find_handler<'a>(path: &[&'a Command]) -> Option<&'a Handler> {
+    path.iter().rev().find_map(|cmd| cmd.handler.as_ref())
+}
+
+fn run_before(hooks: &[&Hooks], parsed: &CommandParsed, ctx: &mut Context) -> Result<(), AppError> {
+    for hook in hooks.iter().flat_map(|hooks| &hooks.before) {
+        (hook.0)(parsed, ctx)?;
+    }
+
+    Ok(())
+}
+
+fn run_after(hooks: &[&Hooks], parsed: &CommandParsed, ctx: &mut Context) -> Result<(), AppError> {
+    for hook in hooks.iter().rev().flat_map(|hooks| &hooks.after) {
+        (hook.0)(parsed, ctx)?;
+    }
+
+    Ok(())
+}
+
+/// Passes the error of `result` through the `on_error` hooks, innermost first.
+fn run_on_error(
+    hooks: &[&Hooks],
+    parsed: &CommandParsed,
+    ctx: &mut Context,
+    result: Result<(), AppError>,
+) -> Result<(), AppError> {
+    let Err(mut error) = result else {
+        return Ok(());
+    };
+
+    for hook in hooks.iter().rev().flat_map(|hooks| &hooks.on_error) {
+        match (hook.0)(parsed, ctx, error) {
+            Ok(()) => return Ok(()),
+            Err(e) => error = e,
+        }
+    }
+
+    Err(error)
+}
@@ -0,0 +1,329 @@
+// Man page and Markdown generators, fed by the same commands, flags, examples and sections as help
+
+use crate::builder::{App, Command};
+use crate::help::{flag_description, flag_row, grouped, usage};
+use crate::style::Styles;
+
+impl App {
+    /// Returns a man page of the [`App`] and every command, in roff for section 1, with the
+    /// long descriptions of `--help`.
+    ///
+    /// Examples of the app and the commands go in `EXAMPLES`, custom sections of the app get a
+    /// section of their own and those of commands go under the command.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust")
+    ///     .version("1.0.0")
+    ///     .description("The Rust toolchain")
+    ///     .command(Command::new("cargo").description("Rust's package manager"))
+    ///     .section("See also", "rustc(1)");
+    ///
+    /// assert_eq!(
+    ///     app.render_man(),
+    ///     concat!(
+    ///         ".TH RUST 1 \"\" \"Rust 1.0.0\"\n",
+    ///         ".SH NAME\n",
+    ///         "Rust \\- The Rust toolchain\n",
+    ///         ".SH SYNOPSIS\n",
+    ///         "Rust <COMMAND>\n",
+    ///         ".SH COMMANDS\n",
+    ///         ".SS \"Rust cargo\"\n",
+    ///         "Rust's package manager\n",
+    ///         ".PP\n",
+    ///         "Usage: Rust cargo\n",
+    ///         ".SH \"SEE ALSO\"\n",
+    ///         ".nf\n",
+    ///         "rustc(1)\n",
+    ///         ".fi\n",
+    ///     )
+    /// );
+    /// ```
+    pub fn render_man(&self) -> String {
+        let mut man = String::new();
+        let title = match &self.version {
+            Some(version) => format!("{} {}", self.name, version),
+            None => self.name.clone(),
+        };
+
+        man.push_str(&format!(
+            ".TH {} 1 \"\" \"{}\"\n",
+            roff_quoted(&self.name.to_uppercase()),
+            roff_quoted(&title)
+        ));
+
+        man.push_str(".SH NAME\n");
+        match &self.description {
+            Some(description) => {
+                man.push_str(&format!("{} \\- {}\n", roff(&self.name), roff(description)))
+            }
+            None => man.push_str(&format!("{}\n", roff(&self.name))),
+        }
+
+        man.push_str(&format!(
+            ".SH SYNOPSIS\n{}\n",
+            roff(&usage(self, &[], &Styles::plain()))
+        ));
+
+        let commands = listed(&self.commands);
+        if !commands.is_empty() {
+            man.push_str(".SH COMMANDS\n");
+        }
+
+        let mut examples: Vec<&(String, String)> = self.examples.iter().collect();
+        let mut path = Vec::new();
+        for cmd in commands {
+            man_command(self, cmd, &mut path, &mut man, &mut examples);
+        }
+
+        if !examples.is_empty() {
+            man.push_str(".SH EXAMPLES\n");
+
+            for (invocation, description) in examples {
+                man.push_str(&format!(".TP\n.B {}\n", roff(invocation)));
+                if !description.is_empty() {
+                    man.push_str(&format!("{}\n", roff(description)));
+                }
+            }
+        }
+
+        for (heading, body) in &self.sections {
+            man.push_str(&format!(
+                ".SH \"{}\"\n.nf\n{}\n.fi\n",
+                roff_quoted(&heading.to_uppercase()),
+                roff(body)
+            ));
+        }
+
+        man
+    }
+
+    /// Returns Markdown documentation of the [`App`] and every command, like a `README` or a
+    /// docs site page, with the long descriptions of `--help`.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo")
+    ///         .flag(Flag::new("offline").description("Run without network"))
+    ///         .example("rust cargo --offline", "Build from the local cache"),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     app.render_markdown(),
+    ///     concat!(
+    ///         "# Rust\n\n",
+    ///         "```\nRust <COMMAND>\n```\n\n",
+    ///         "## Commands\n\n",
+    ///         "### `Rust cargo`\n\n",
+    ///         "```\nRust cargo [FLAGS]\n```\n\n",
+    ///         "| Flag | Description |\n",
+    ///         "| --- | --- |\n",
+    ///         "| `--offline` | Run without network |\n\n",
+    ///         "#### Examples\n\n",
+    ///         "Build from the local cache\n\n",
+    ///         "```\nrust cargo --offline\n```\n\n",
+    ///     )
+    /// );
+    /// ```
+    pub fn render_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n", self.name);
+
+        if let Some(version) = &self.version {
+            markdown.push_str(&format!("Version: `{}`\n\n", version));
+        }
+
+        if let Some(description) = &self.description {
+            markdown.push_str(&format!("{}\n\n", description));
+        }
+
+        markdown.push_str(&format!(
+            "```\n{}\n```\n\n",
+            usage(self, &[], &Styles::plain())
+        ));
+
+        let commands = listed(&self.commands);
+        if !commands.is_empty() {
+            markdown.push_str("## Commands\n\n");
+        }
+
+        let mut path = Vec::new();
+        for cmd in commands {
+            markdown_command(self, cmd, &mut path, &mut markdown);
+        }
+
+        markdown_examples(&mut markdown, "##", &self.examples);
+
+        for (heading, body) in &self.sections {
+            markdown.push_str(&format!("## {}\n\n{}\n\n", heading, body));
+        }
+
+        markdown
+    }
+}
+
+/// Pushes the subsection of `cmd` and its subcommands, and collects their examples.
+fn man_command<'a>(
+    app: &App,
+    cmd: &'a Command,
+    path: &mut Vec<&'a Command>,
+    man: &mut String,
+    examples: &mut Vec<&'a (String, String)>,
+) {
+    path.push(cmd);
+
+    let names: Vec<&str> = path.iter().map(|cmd| cmd.name.as_str()).collect();
+    man.push_str(&format!(
+        ".SS \"{} {}\"\n",
+        roff_quoted(&app.name),
+        roff_quoted(&names.join(" "))
+    ));
+
+    if let Some(description) = cmd.get_help_description(true) {
+        man.push_str(&format!("{}\n", roff(description)));
+    }
+
+    man.push_str(&format!(
+        ".PP\nUsage: {}\n",
+        roff(&usage(app, path, &Styles::plain()))
+    ));
+
+    for (heading, flags) in grouped(&cmd.flags) {
+        if let Some(heading) = heading {
+            man.push_str(&format!(".PP\n.B \"{}\"\n", roff_quoted(heading)));
+        }
+
+        for flag in flags {
+            let row = flag_row(flag, &Styles::plain(), true);
+            man.push_str(&format!(".TP\n.B {}\n", roff(row.left.trim_start())));
+
+            if !row.right.is_empty() {
+                man.push_str(&format!("{}\n", roff(&row.right)));
+            }
+        }
+    }
+
+    for (heading, body) in &cmd.sections {
+        man.push_str(&format!(
+            ".PP\n.B \"{}\"\n.nf\n{}\n.fi\n",
+            roff_quoted(heading),
+            roff(body)
+        ));
+    }
+
+    examples.extend(&cmd.examples);
+
+    for subcommand in listed(&cmd.subcommands) {
+        man_command(app, subcommand, path, man, examples);
+    }
+
+    path.pop();
+}
+
+/// Pushes the section of `cmd` and its subcommands.
+fn markdown_command<'a>(
+    app: &App,
+    cmd: &'a Command,
+    path: &mut Vec<&'a Command>,
+    markdown: &mut String,
+) {
+    path.push(cmd);
+
+    let names: Vec<&str> = path.iter().map(|cmd| cmd.name.as_str()).collect();
+    markdown.push_str(&format!("### `{} {}`\n\n", app.name, names.join(" ")));
+
+    if let Some(description) = cmd.get_help_description(true) {
+        markdown.push_str(&format!("{}\n\n", description));
+    }
+
+    markdown.push_str(&format!(
+        "```\n{}\n```\n\n",
+        usage(app, path, &Styles::plain())
+    ));
+
+    for (heading, flags) in grouped(&cmd.flags) {
+        if let Some(heading) = heading {
+            markdown.push_str(&format!("#### {}\n\n", heading));
+        }
+
+        markdown.push_str("| Flag | Description |\n| --- | --- |\n");
+
+        for flag in flags {
+            let row = flag_row(flag, &Styles::plain(), true);
+            markdown.push_str(&format!(
+                "| `{}` | {} |\n",
+                table_cell(row.left.trim_start()),
+                table_cell(&flag_description(flag, true))
+            ));
+        }
+
+        markdown.push('\n');
+    }
+
+    markdown_examples(markdown, "####", &cmd.examples);
+
+    for (heading, body) in &cmd.sections {
+        markdown.push_str(&format!("#### {}\n\n{}\n\n", heading, body));
+    }
+
+    for subcommand in listed(&cmd.subcommands) {
+        markdown_command(app, subcommand, path, markdown);
+    }
+
+    path.pop();
+}
+
+/// Pushes the examples under a `level` heading, each description followed by its invocation.
+fn markdown_examples(markdown: &mut String, level: &str, examples: &[(String, String)]) {
+    if examples.is_empty() {
+        return;
+    }
+
+    markdown.push_str(&format!("{} Examples\n\n", level));
+
+    for (invocation, description) in examples {
+        if !description.is_empty() {
+            markdown.push_str(&format!("{}\n\n", description));
+        }
+
+        markdown.push_str(&format!("```\n{}\n```\n\n", invocation));
+    }
+}
+
+/// Returns the commands that aren't hidden, in the order help lists them.
+fn listed(commands: &[Command]) -> Vec<&Command> {
+    grouped(commands)
+        .into_iter()
+        .flat_map(|(_, commands)| commands)
+        .collect()
+}
+
+/// Escapes `text` for roff: backslashes and hyphens, and dots or quotes that start a line.
+fn roff(text: &str) -> String {
+    text.replace('\\', "\\e")
+        .replace('-', "\\-")
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escapes `text` for a quoted macro argument, like `.SH "<text>"`, where a `"` would end it.
+fn roff_quoted(text: &str) -> String {
+    roff(text).replace('"', "\\(dq")
+}
+
+/// Escapes the pipes and newlines of a Markdown table cell.
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
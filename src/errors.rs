@@ -1,19 +1,427 @@
 use std::error::Error;
+use std::io::{self, IsTerminal};
+use std::process::{ExitCode, Termination};
 use std::{fmt, process};
 
+use crate::json;
+use crate::style::{ColorChoice, Styles};
+
 #[derive(Debug)]
 pub enum AppError {
     IoError(std::io::Error),
     InvalidInput(String),
     InvalidCommand(String),
     InvalidFlag(String),
+    MissingValue(String),
+    UnexpectedValue(String),
+    InvalidOccurrences(String),
+    InvalidValue(String),
+    DuplicateKey(String),
+    AmbiguousCommand(String, Vec<String>),
+    AmbiguousFlag(String, Vec<String>),
+    InvalidDefinition(Vec<ValidationProblem>),
+    AsyncHandler(String),
+    /// An experimental command or flag used while the unstable gate is closed.
+    Experimental(String),
+    /// Not a failure: the help requested with `-h` or `--help`.
+    DisplayHelp(String),
+    /// Not a failure: the version requested with `--version`.
+    DisplayVersion(String),
+    /// Every error found with [`App::collect_errors()`](crate::builder::App::collect_errors) set,
+    /// in the order they were found.
+    Multiple(Vec<AppError>),
+    Unknown,
+}
+
+/// The kind of an [`AppError`], used to pick its exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Io,
+    InvalidInput,
+    InvalidCommand,
+    InvalidFlag,
+    MissingValue,
+    UnexpectedValue,
+    InvalidOccurrences,
+    InvalidValue,
+    DuplicateKey,
+    AmbiguousCommand,
+    AmbiguousFlag,
+    InvalidDefinition,
+    AsyncHandler,
+    Experimental,
+    DisplayHelp,
+    DisplayVersion,
+    Multiple,
     Unknown,
 }
 
+impl ErrorKind {
+    /// Returns the name of the kind in `snake_case`, as written in JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io",
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::InvalidCommand => "invalid_command",
+            ErrorKind::InvalidFlag => "invalid_flag",
+            ErrorKind::MissingValue => "missing_value",
+            ErrorKind::UnexpectedValue => "unexpected_value",
+            ErrorKind::InvalidOccurrences => "invalid_occurrences",
+            ErrorKind::InvalidValue => "invalid_value",
+            ErrorKind::DuplicateKey => "duplicate_key",
+            ErrorKind::AmbiguousCommand => "ambiguous_command",
+            ErrorKind::AmbiguousFlag => "ambiguous_flag",
+            ErrorKind::InvalidDefinition => "invalid_definition",
+            ErrorKind::AsyncHandler => "async_handler",
+            ErrorKind::Experimental => "experimental",
+            ErrorKind::DisplayHelp => "display_help",
+            ErrorKind::DisplayVersion => "display_version",
+            ErrorKind::Multiple => "multiple",
+            ErrorKind::Unknown => "unknown",
+        }
+    }
+
+    /// Returns the default exit code of the kind, following `sysexits.h`:
+    /// - `0` for [`ErrorKind::DisplayHelp`] and [`ErrorKind::DisplayVersion`].
+    /// - `64` (`EX_USAGE`) for args that don't fit the app.
+    /// - `65` (`EX_DATAERR`) for [`ErrorKind::InvalidValue`] and [`ErrorKind::DuplicateKey`].
+    /// - `70` (`EX_SOFTWARE`) for mistakes in the app itself, like
+    ///   [`ErrorKind::InvalidDefinition`].
+    /// - `74` (`EX_IOERR`) for [`ErrorKind::Io`].
+    ///
+    /// [`ErrorKind::Multiple`] has no code of its own, [`AppError::exit_code()`] uses the code of
+    /// the first error, `64` is only returned here.
+    ///
+    /// Use [`App::exit_code()`](crate::builder::App::exit_code) to change them.
+    pub fn default_exit_code(self) -> u8 {
+        match self {
+            ErrorKind::DisplayHelp | ErrorKind::DisplayVersion => 0,
+            ErrorKind::InvalidInput
+            | ErrorKind::InvalidCommand
+            | ErrorKind::InvalidFlag
+            | ErrorKind::MissingValue
+            | ErrorKind::UnexpectedValue
+            | ErrorKind::InvalidOccurrences
+            | ErrorKind::AmbiguousCommand
+            | ErrorKind::AmbiguousFlag
+            | ErrorKind::Experimental
+            | ErrorKind::Multiple => 64,
+            ErrorKind::InvalidValue | ErrorKind::DuplicateKey => 65,
+            ErrorKind::InvalidDefinition | ErrorKind::AsyncHandler | ErrorKind::Unknown => 70,
+            ErrorKind::Io => 74,
+        }
+    }
+}
+
+/// A single problem found by [`App::validate()`](crate::builder::App::validate).
+///
+/// `scope` is the space separated path of the command that owns the offending item, or the app
+/// name for top-level commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationProblem {
+    DuplicateCommand { scope: String, name: String },
+    DuplicateFlag { scope: String, long: String },
+    ShortCollision { scope: String, short: char },
+    InvalidCommandName { scope: String, name: String },
+    InvalidFlagName { scope: String, long: String },
+    InvalidShort { scope: String, short: char },
+    InvalidOccurrences { scope: String, long: String },
+    InvalidDelimiter { scope: String, long: String },
+}
+
+/// How errors are printed by [`App::report()`](crate::builder::App::report) and the
+/// `parse_*` methods that exit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// `Error: <kind>: <message>`, for people.
+    #[default]
+    Text,
+    /// A JSON document, for scripts and editors, see [`Diagnostic::to_json()`].
+    Json,
+}
+
+/// An [`AppError`] along with the arg that caused it and the names the user may have meant.
+///
+/// Returned by [`App::try_parse_diagnostics_from()`](crate::builder::App::try_parse_diagnostics_from).
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub(crate) error: AppError,
+    pub(crate) token: Option<String>,
+    pub(crate) position: Option<usize>,
+    pub(crate) suggestions: Vec<String>,
+    pub(crate) error_format: ErrorFormat,
+    pub(crate) color: ColorChoice,
+    pub(crate) styled_help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn get_error(&self) -> &AppError {
+        &self.error
+    }
+
+    pub fn into_error(self) -> AppError {
+        self.error
+    }
+
+    /// Returns the arg that caused the error, or `None` if it isn't caused by a single arg, like
+    /// a flag that occurs too many times.
+    pub fn get_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Returns the index of [`Diagnostic::get_token()`] in the args, counted like
+    /// [`CommandParsed::get_flag_indices()`](crate::parser::CommandParsed::get_flag_indices).
+    pub fn get_position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Returns the commands or flags with a name close to the token, or the candidates of an
+    /// ambiguous prefix.
+    pub fn get_suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    /// Returns the format picked for the parse: the built-in `--error-format` flag if the parser
+    /// reached it, otherwise [`App::error_format()`](crate::builder::App::error_format).
+    ///
+    /// Parsing stops at the first error unless
+    /// [`App::collect_errors()`](crate::builder::App::collect_errors) is set, so a flag given
+    /// after the error only counts when errors are collected, then it applies to every error.
+    pub fn get_error_format(&self) -> ErrorFormat {
+        self.error_format
+    }
+
+    /// Returns the color choice picked for the parse, like [`Diagnostic::get_error_format()`].
+    pub fn get_color(&self) -> ColorChoice {
+        self.color
+    }
+
+    /// Returns the help of an [`AppError::DisplayHelp`] painted with
+    /// [`App::styles()`](crate::builder::App::styles), or `None` if the color choice is
+    /// [`ColorChoice::Never`].
+    ///
+    /// The error itself always holds the plain help, this one is printed instead when stdout is
+    /// styled.
+    pub fn get_styled_help(&self) -> Option<&str> {
+        self.styled_help.as_deref()
+    }
+
+    /// Returns the diagnostic as a JSON object:
+    ///
+    /// ```json
+    /// {
+    ///   "kind": "invalid_flag",
+    ///   "message": "Flag not found: --relase for `build`",
+    ///   "token": "--relase",
+    ///   "position": 3,
+    ///   "suggestions": ["--release"]
+    /// }
+    /// ```
+    ///
+    /// `kind` is one of [`ErrorKind::as_str()`], `token` and `position` may be `null`. New fields
+    /// may be added, existing fields won't change.
+    pub fn to_json(&self) -> String {
+        error_json(
+            &self.error,
+            self.token.as_deref(),
+            self.position,
+            &self.suggestions,
+        )
+    }
+
+    /// Returns every diagnostic as a JSON document: `{"errors": [...]}`, see
+    /// [`Diagnostic::to_json()`].
+    pub fn list_to_json(diagnostics: &[Diagnostic]) -> String {
+        json::object([(
+            "errors",
+            json::array(diagnostics.iter().map(Diagnostic::to_json)),
+        )])
+    }
+}
+
+/// A diagnostic without a token or position, with the default format and color.
+impl From<AppError> for Diagnostic {
+    fn from(error: AppError) -> Diagnostic {
+        Diagnostic {
+            suggestions: error.candidates(),
+            error,
+            token: None,
+            position: None,
+            error_format: ErrorFormat::default(),
+            color: ColorChoice::default(),
+            styled_help: None,
+        }
+    }
+}
+
+fn error_json(
+    error: &AppError,
+    token: Option<&str>,
+    position: Option<usize>,
+    suggestions: &[String],
+) -> String {
+    let message = error.to_string();
+    let message = message.strip_prefix("Error: ").unwrap_or(&message);
+
+    json::object([
+        ("kind", json::string(error.kind().as_str())),
+        ("message", json::string(message)),
+        ("token", json::optional_string(token)),
+        (
+            "position",
+            position.map_or("null".to_string(), |p| p.to_string()),
+        ),
+        (
+            "suggestions",
+            json::strings(suggestions.iter().map(String::as_str)),
+        ),
+    ])
+}
+
 impl AppError {
+    /// Returns the error as a JSON document without tokens or positions, see
+    /// [`Diagnostic::list_to_json()`].
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::errors::AppError;
+    ///
+    /// let error = AppError::MissingValue("jobs".to_string());
+    ///
+    /// assert_eq!(
+    ///     error.to_json(),
+    ///     r#"{"errors":[{"kind":"missing_value","message":"Missing value: `--jobs` requires a value","token":null,"position":null,"suggestions":[]}]}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let errors = match self {
+            AppError::Multiple(errors) => errors.iter().collect(),
+            error => vec![error],
+        };
+
+        let errors = errors
+            .into_iter()
+            .map(|error| error_json(error, None, None, &error.candidates()));
+
+        json::object([("errors", json::array(errors))])
+    }
+
+    /// Returns the candidates of an ambiguous command or flag.
+    pub(crate) fn candidates(&self) -> Vec<String> {
+        match self {
+            AppError::AmbiguousCommand(_, candidates) => candidates.clone(),
+            AppError::AmbiguousFlag(_, candidates) => {
+                candidates.iter().map(|c| format!("--{}", c)).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AppError::IoError(_) => ErrorKind::Io,
+            AppError::InvalidInput(_) => ErrorKind::InvalidInput,
+            AppError::InvalidCommand(_) => ErrorKind::InvalidCommand,
+            AppError::InvalidFlag(_) => ErrorKind::InvalidFlag,
+            AppError::MissingValue(_) => ErrorKind::MissingValue,
+            AppError::UnexpectedValue(_) => ErrorKind::UnexpectedValue,
+            AppError::InvalidOccurrences(_) => ErrorKind::InvalidOccurrences,
+            AppError::InvalidValue(_) => ErrorKind::InvalidValue,
+            AppError::DuplicateKey(_) => ErrorKind::DuplicateKey,
+            AppError::AmbiguousCommand(..) => ErrorKind::AmbiguousCommand,
+            AppError::AmbiguousFlag(..) => ErrorKind::AmbiguousFlag,
+            AppError::InvalidDefinition(_) => ErrorKind::InvalidDefinition,
+            AppError::AsyncHandler(_) => ErrorKind::AsyncHandler,
+            AppError::Experimental(_) => ErrorKind::Experimental,
+            AppError::DisplayHelp(_) => ErrorKind::DisplayHelp,
+            AppError::DisplayVersion(_) => ErrorKind::DisplayVersion,
+            AppError::Multiple(_) => ErrorKind::Multiple,
+            AppError::Unknown => ErrorKind::Unknown,
+        }
+    }
+
+    /// Returns `true` for help and version output, which aren't failures.
+    pub fn is_display(&self) -> bool {
+        matches!(self, AppError::DisplayHelp(_) | AppError::DisplayVersion(_))
+    }
+
+    /// Returns the default exit code of the error, see [`ErrorKind::default_exit_code()`].
+    ///
+    /// [`AppError::Multiple`] returns the code of its first error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AppError::Multiple(errors) if !errors.is_empty() => errors[0].exit_code(),
+            error => error.kind().default_exit_code(),
+        }
+    }
+
+    /// Prints the error and exits the process with its default exit code.
+    ///
+    /// Help and version output goes to stdout, errors to stderr. This doesn't run destructors,
+    /// prefer returning the error from `main`, see the [`Termination`] impl.
     pub fn exit(&self) -> ! {
-        eprintln!("{}", self);
-        process::exit(1);
+        self.print(&Styles::default(), ColorChoice::Auto, &[]);
+        process::exit(self.exit_code().into());
+    }
+
+    /// Prints the error, help and version output to stdout and errors to stderr.
+    ///
+    /// Errors get their `Error:` prefix painted and are followed by the `suggestions` for a typo,
+    /// help and version output is printed as is.
+    pub(crate) fn print(&self, styles: &Styles, color: ColorChoice, suggestions: &[String]) {
+        if self.is_display() {
+            println!("{}", self);
+            return;
+        }
+
+        let styles = if color.enables_color(io::stderr().is_terminal()) {
+            *styles
+        } else {
+            Styles::plain()
+        };
+
+        let message = self.to_string();
+        match message.strip_prefix("Error:") {
+            Some(message) => eprintln!("{}{}", styles.error.paint("Error:"), message),
+            None => eprintln!("{}", message),
+        }
+
+        if !suggestions.is_empty() {
+            let suggestions: Vec<String> = suggestions
+                .iter()
+                .map(|suggestion| styles.suggestion.paint(suggestion))
+                .collect();
+
+            eprintln!("\n  tip: did you mean {}?", suggestions.join(", "));
+        }
+    }
+}
+
+/// Prints the error and returns its default exit code, so `main` can return it.
+///
+/// This only knows the defaults of [`ErrorKind::default_exit_code()`], with default styles and
+/// color. Use [`App::report()`](crate::builder::App::report) to get the codes set with
+/// [`App::exit_code()`](crate::builder::App::exit_code) and the color and format chosen for
+/// the app.
+///
+/// # Example:
+/// ```rust,no_run
+/// use ecp::builder::*;
+/// use std::process::{ExitCode, Termination};
+///
+/// fn main() -> ExitCode {
+///     let app = App::new("Rust").command(Command::new("cargo"));
+///
+///     match app.try_run() {
+///         Ok(cli) => cli.report(),
+///         Err(e) => e.report(),
+///     }
+/// }
+/// ```
+impl Termination for AppError {
+    fn report(self) -> ExitCode {
+        self.print(&Styles::default(), ColorChoice::Auto, &[]);
+        ExitCode::from(self.exit_code())
     }
 }
 
@@ -24,11 +432,118 @@ impl fmt::Display for AppError {
             AppError::InvalidInput(msg) => write!(f, "Error: Invalid input: {}", msg),
             AppError::InvalidCommand(cmd) => write!(f, "Error: Invalid command: {}", cmd),
             AppError::InvalidFlag(flag) => write!(f, "Error: Invalid flag: {}", flag),
+            AppError::MissingValue(flag) => {
+                write!(f, "Error: Missing value: `--{}` requires a value", flag)
+            }
+            AppError::UnexpectedValue(flag) => {
+                write!(
+                    f,
+                    "Error: Unexpected value: `--{}` doesn't take a value",
+                    flag
+                )
+            }
+            AppError::InvalidOccurrences(msg) => {
+                write!(f, "Error: Invalid number of occurrences: {}", msg)
+            }
+            AppError::InvalidValue(msg) => write!(f, "Error: Invalid value: {}", msg),
+            AppError::DuplicateKey(key) => write!(f, "Error: Duplicate key: {}", key),
+            AppError::AmbiguousCommand(cmd, candidates) => write!(
+                f,
+                "Error: Ambiguous command: `{}` could be {}",
+                cmd,
+                candidates_list(candidates, "")
+            ),
+            AppError::AmbiguousFlag(flag, candidates) => write!(
+                f,
+                "Error: Ambiguous flag: `--{}` could be {}",
+                flag,
+                candidates_list(candidates, "--")
+            ),
+            AppError::InvalidDefinition(problems) => {
+                write!(f, "Error: Invalid app definition:")?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+                Ok(())
+            }
+            AppError::AsyncHandler(path) => write!(
+                f,
+                "Error: Async handler: `{}` has an async handler, dispatch it with `App::try_dispatch_async_from()`",
+                path
+            ),
+            AppError::Experimental(msg) => write!(f, "Error: Experimental: {}", msg),
+            AppError::DisplayHelp(help) => write!(f, "{}", help.trim_end()),
+            AppError::DisplayVersion(version) => write!(f, "{}", version),
+            AppError::Multiple(errors) => {
+                write!(f, "Error: {} errors:", errors.len())?;
+                for error in errors {
+                    let error = error.to_string();
+                    write!(
+                        f,
+                        "\n  - {}",
+                        error.strip_prefix("Error: ").unwrap_or(&error)
+                    )?;
+                }
+                Ok(())
+            }
             AppError::Unknown => write!(f, "Error: Unknown error"),
         }
     }
 }
 
+fn candidates_list(candidates: &[String], prefix: &str) -> String {
+    candidates
+        .iter()
+        .map(|candidate| format!("`{}{}`", prefix, candidate))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationProblem::DuplicateCommand { scope, name } => {
+                write!(f, "{}: command `{}` is defined more than once", scope, name)
+            }
+            ValidationProblem::DuplicateFlag { scope, long } => {
+                write!(f, "{}: flag `--{}` is defined more than once", scope, long)
+            }
+            ValidationProblem::ShortCollision { scope, short } => {
+                write!(
+                    f,
+                    "{}: short flag `-{}` is used by more than one flag",
+                    scope, short
+                )
+            }
+            ValidationProblem::InvalidCommandName { scope, name } => {
+                write!(f, "{}: `{}` is not a valid command name", scope, name)
+            }
+            ValidationProblem::InvalidFlagName { scope, long } => {
+                write!(f, "{}: `{}` is not a valid flag name", scope, long)
+            }
+            ValidationProblem::InvalidShort { scope, short } => {
+                write!(f, "{}: `{}` is not a valid short flag", scope, short)
+            }
+            ValidationProblem::InvalidOccurrences { scope, long } => write!(
+                f,
+                "{}: flag `--{}` has a maximum number of occurrences lower than its minimum",
+                scope, long
+            ),
+            ValidationProblem::InvalidDelimiter { scope, long } => write!(
+                f,
+                "{}: key-value flag `--{}` can't have `=` as value delimiter",
+                scope, long
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> AppError {
+        AppError::IoError(e)
+    }
+}
+
 impl Error for AppError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
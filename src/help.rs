@@ -0,0 +1,571 @@
+use crate::builder::{App, Command, Flag};
+use crate::errors::AppError;
+use crate::style::Styles;
+use crate::text::{display_width, wrap};
+
+/// The narrowest the description column gets before descriptions go below the names.
+const MIN_DESCRIPTION_WIDTH: usize = 20;
+
+/// The indent of descriptions that go below the names.
+const NEXT_LINE_INDENT: usize = 8;
+
+/// The indent of the descriptions of examples.
+const EXAMPLE_INDENT: usize = 4;
+
+impl App {
+    /// Returns the help of the [`App`]: its name, version, description and commands.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust")
+    ///     .version("0.1.0")
+    ///     .command(Command::new("cargo").description("Rust's package manager"));
+    ///
+    /// assert_eq!(
+    ///     app.render_help(),
+    ///     "Rust 0.1.0\n\nUsage: Rust <COMMAND>\n\nCommands:\n  cargo  Rust's package manager\n"
+    /// );
+    /// ```
+    pub fn render_help(&self) -> String {
+        render_app(self, &Styles::plain())
+    }
+
+    /// Returns the help of the command found by following `path` from the top-level commands,
+    /// names and aliases are accepted.
+    ///
+    /// # Errors:
+    /// Returns [`AppError::InvalidCommand`] if `path` is empty or doesn't lead to a command.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo").subcommand(
+    ///         Command::new("build")
+    ///             .description("Compile the current package")
+    ///             .flag(Flag::new("release").short('r').description("Build in release mode")),
+    ///     ),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     app.render_command_help(&["cargo", "build"]).unwrap(),
+    ///     "Compile the current package\n\nUsage: Rust cargo build [FLAGS]\n\nFlags:\n  -r, --release  Build in release mode\n"
+    /// );
+    /// ```
+    pub fn render_command_help(&self, path: &[&str]) -> Result<String, AppError> {
+        let found = self.find_commands(path)?;
+        Ok(render_path(self, &found, &Styles::plain(), false))
+    }
+
+    /// Returns the long help of a command, like [`App::render_command_help()`] but with the
+    /// [`Command::long_description()`] and [`Flag::long_description()`] of the command and its
+    /// flags, when they have one. This is the help printed for `--help`, `-h` prints the short
+    /// one.
+    ///
+    /// # Errors:
+    /// Returns [`AppError::InvalidCommand`] if `path` is empty or doesn't lead to a command.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("build")
+    ///         .description("Compile the current package")
+    ///         .long_description("Compile the current package and all of its dependencies")
+    ///         .flag(Flag::new("release").description("Build in release mode")),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     app.render_long_command_help(&["build"]).unwrap(),
+    ///     "Compile the current package and all of its dependencies\n\nUsage: Rust build [FLAGS]\n\nFlags:\n      --release  Build in release mode\n"
+    /// );
+    /// ```
+    pub fn render_long_command_help(&self, path: &[&str]) -> Result<String, AppError> {
+        let found = self.find_commands(path)?;
+        Ok(render_path(self, &found, &Styles::plain(), true))
+    }
+
+    /// Returns the commands found by following `path` from the top-level commands.
+    fn find_commands(&self, path: &[&str]) -> Result<Vec<&Command>, AppError> {
+        let mut commands = &self.commands;
+        let mut found = Vec::new();
+
+        for name in path {
+            let cmd = commands
+                .iter()
+                .find(|cmd| cmd.get_names().any(|n| n == *name))
+                .ok_or_else(|| AppError::InvalidCommand(format!("Command not found: {}", name)))?;
+
+            found.push(cmd);
+            commands = &cmd.subcommands;
+        }
+
+        if found.is_empty() {
+            return Err(AppError::InvalidCommand("Command not found: ".to_string()));
+        }
+
+        Ok(found)
+    }
+}
+
+/// A row of a help section, `left` may contain escape codes so its `width` is kept apart.
+pub(crate) struct Row {
+    pub(crate) left: String,
+    pub(crate) width: usize,
+    pub(crate) right: String,
+}
+
+/// Returns the help of the app, painted with `styles`.
+fn render_app(app: &App, styles: &Styles) -> String {
+    let width = app.help_width();
+
+    let mut title = match &app.version {
+        Some(version) => format!("{} {}\n", app.name, version),
+        None => format!("{}\n", app.name),
+    };
+    let description = wrapped(app.description.as_deref().unwrap_or_default(), width);
+    title.push_str(&description);
+
+    let usage = format!(
+        "{} {}\n",
+        styles.header.paint("Usage:"),
+        usage(app, &[], styles)
+    );
+
+    let blocks = Blocks {
+        name: &app.name,
+        version: app.version.as_deref().unwrap_or_default(),
+        description,
+        usage,
+        subcommands: commands_section("Commands", &app.commands, styles, width),
+        flags: String::new(),
+        examples: examples_section(&app.examples, styles, width),
+        sections: custom_sections(&app.sections, styles, width),
+    };
+
+    match &app.help_template {
+        Some(template) => blocks.fill(template),
+        None => join_blocks([
+            title,
+            blocks.usage,
+            blocks.subcommands,
+            blocks.examples,
+            blocks.sections,
+        ]),
+    }
+}
+
+/// Returns the help of the last command in `path`, or of the app if `path` is empty, painted
+/// with `styles`. The long help uses long descriptions where they're set.
+pub(crate) fn render_path(app: &App, path: &[&Command], styles: &Styles, long: bool) -> String {
+    let Some(cmd) = path.last() else {
+        return render_app(app, styles);
+    };
+
+    let width = app.help_width();
+
+    let blocks = Blocks {
+        name: &app.name,
+        version: app.version.as_deref().unwrap_or_default(),
+        description: wrapped(cmd.get_help_description(long).unwrap_or_default(), width),
+        usage: format!(
+            "{} {}\n",
+            styles.header.paint("Usage:"),
+            usage(app, path, styles)
+        ),
+        subcommands: commands_section("Subcommands", &cmd.subcommands, styles, width),
+        flags: flags_section(&cmd.flags, styles, width, long),
+        examples: examples_section(&cmd.examples, styles, width),
+        sections: custom_sections(&cmd.sections, styles, width),
+    };
+
+    match cmd.help_template.as_ref().or(app.help_template.as_ref()) {
+        Some(template) => blocks.fill(template),
+        None => join_blocks([
+            blocks.description,
+            blocks.usage,
+            blocks.subcommands,
+            blocks.flags,
+            blocks.examples,
+            blocks.sections,
+        ]),
+    }
+}
+
+/// Returns the usage of the last command in `path`, like `Rust cargo build [FLAGS]`.
+pub(crate) fn usage(app: &App, path: &[&Command], styles: &Styles) -> String {
+    let Some(cmd) = path.last() else {
+        return format!(
+            "{} {}",
+            styles.literal.paint(&app.name),
+            styles.placeholder.paint("<COMMAND>")
+        );
+    };
+
+    let mut names = app.name.clone();
+    for cmd in path {
+        names.push(' ');
+        names.push_str(&cmd.name);
+    }
+
+    let mut usage = styles.literal.paint(&names);
+    if cmd.flags.iter().any(|flag| !flag.hidden) {
+        usage.push_str(&format!(" {}", styles.placeholder.paint("[FLAGS]")));
+    }
+    if cmd.subcommands.iter().any(|cmd| !cmd.hidden) {
+        usage.push_str(&format!(" {}", styles.placeholder.paint("[SUBCOMMAND]")));
+    }
+
+    usage
+}
+
+/// The parts of a help that a template can place, each is empty or ends with a newline.
+struct Blocks<'a> {
+    name: &'a str,
+    version: &'a str,
+    description: String,
+    usage: String,
+    subcommands: String,
+    flags: String,
+    examples: String,
+    sections: String,
+}
+
+impl Blocks<'_> {
+    /// Replaces the placeholders of `template` with the blocks, without their last newline.
+    fn fill(&self, template: &str) -> String {
+        let placeholders = [
+            ("{name}", self.name),
+            ("{version}", self.version),
+            ("{description}", &self.description),
+            ("{usage}", &self.usage),
+            ("{subcommands}", &self.subcommands),
+            ("{flags}", &self.flags),
+            ("{examples}", &self.examples),
+            ("{sections}", &self.sections),
+        ];
+
+        // A single pass, so that a placeholder inside a block is left as written.
+        let mut help = String::with_capacity(template.len());
+        let mut rest = template;
+        'outer: while let Some(start) = rest.find('{') {
+            help.push_str(&rest[..start]);
+            rest = &rest[start..];
+            for (placeholder, block) in placeholders {
+                if let Some(after) = rest.strip_prefix(placeholder) {
+                    help.push_str(block.strip_suffix('\n').unwrap_or(block));
+                    rest = after;
+                    continue 'outer;
+                }
+            }
+            help.push('{');
+            rest = &rest[1..];
+        }
+        help.push_str(rest);
+
+        if !help.ends_with('\n') {
+            help.push('\n');
+        }
+
+        help
+    }
+}
+
+/// Joins the blocks that aren't empty with blank lines.
+fn join_blocks<const N: usize>(blocks: [String; N]) -> String {
+    blocks
+        .into_iter()
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns `text` wrapped to `width` columns, or nothing if it's empty.
+fn wrapped(text: &str, width: usize) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+
+    wrap(text, width)
+        .into_iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// A command or flag listed in help.
+pub(crate) trait Listed {
+    fn is_listed(&self) -> bool;
+    fn heading(&self) -> Option<&str>;
+    fn order(&self) -> Option<usize>;
+}
+
+impl Listed for Command {
+    fn is_listed(&self) -> bool {
+        !self.hidden
+    }
+
+    fn heading(&self) -> Option<&str> {
+        self.help_heading.as_deref()
+    }
+
+    fn order(&self) -> Option<usize> {
+        self.display_order
+    }
+}
+
+impl Listed for Flag {
+    fn is_listed(&self) -> bool {
+        !self.hidden
+    }
+
+    fn heading(&self) -> Option<&str> {
+        self.help_heading.as_deref()
+    }
+
+    fn order(&self) -> Option<usize> {
+        self.display_order
+    }
+}
+
+/// Returns the items that aren't hidden grouped by heading: items without one first, then every
+/// heading in the order it first appears. Items are sorted by display order, then by definition
+/// order.
+pub(crate) fn grouped<T: Listed>(items: &[T]) -> Vec<(Option<&str>, Vec<&T>)> {
+    let mut listed: Vec<&T> = items.iter().filter(|item| item.is_listed()).collect();
+    listed.sort_by_key(|item| item.order().unwrap_or(usize::MAX));
+
+    let mut groups: Vec<(Option<&str>, Vec<&T>)> = vec![(None, Vec::new())];
+    for item in listed {
+        match groups
+            .iter_mut()
+            .find(|(heading, _)| *heading == item.heading())
+        {
+            Some((_, group)) => group.push(item),
+            None => groups.push((item.heading(), vec![item])),
+        }
+    }
+
+    groups.retain(|(_, group)| !group.is_empty());
+    groups
+}
+
+/// Returns the commands that aren't hidden, aligned with their descriptions under `heading` or
+/// their own headings.
+fn commands_section(heading: &str, commands: &[Command], styles: &Styles, width: usize) -> String {
+    let sections: Vec<String> = grouped(commands)
+        .into_iter()
+        .map(|(own_heading, commands)| {
+            let rows: Vec<Row> = commands
+                .into_iter()
+                .map(|cmd| {
+                    let names: Vec<&str> = std::iter::once(cmd.name.as_str())
+                        .chain(cmd.get_visible_aliases())
+                        .collect();
+                    let names = names.join(", ");
+
+                    Row {
+                        left: styles.literal.paint(&names),
+                        width: display_width(&names),
+                        right: labeled(
+                            cmd.description.clone().unwrap_or_default(),
+                            cmd.deprecated.is_some(),
+                            cmd.experimental,
+                        ),
+                    }
+                })
+                .collect();
+
+            section(own_heading.unwrap_or(heading), &rows, styles, width)
+        })
+        .collect();
+
+    sections.join("\n")
+}
+
+/// Returns the flags that aren't hidden, under `Flags:` or their own headings.
+fn flags_section(flags: &[Flag], styles: &Styles, width: usize, long: bool) -> String {
+    let sections: Vec<String> = grouped(flags)
+        .into_iter()
+        .map(|(heading, flags)| {
+            let rows: Vec<Row> = flags
+                .into_iter()
+                .map(|flag| flag_row(flag, styles, long))
+                .collect();
+
+            section(heading.unwrap_or("Flags"), &rows, styles, width)
+        })
+        .collect();
+
+    sections.join("\n")
+}
+
+/// Returns the examples, each invocation followed by its description.
+fn examples_section(examples: &[(String, String)], styles: &Styles, width: usize) -> String {
+    if examples.is_empty() {
+        return String::new();
+    }
+
+    let mut help = format!("{}\n", styles.header.paint("Examples:"));
+
+    for (invocation, description) in examples {
+        help.push_str(&format!("  $ {}\n", styles.literal.paint(invocation)));
+
+        if !description.is_empty() {
+            for line in wrap(description, width.saturating_sub(EXAMPLE_INDENT)) {
+                push_indented(&mut help, &line, EXAMPLE_INDENT);
+            }
+        }
+    }
+
+    help
+}
+
+/// Returns the custom sections, like `Environment:` or `See also:`, with their bodies indented.
+fn custom_sections(sections: &[(String, String)], styles: &Styles, width: usize) -> String {
+    let sections: Vec<String> = sections
+        .iter()
+        .map(|(heading, body)| {
+            let mut help = format!("{}\n", styles.header.paint(&format!("{}:", heading)));
+
+            for line in wrap(body, width.saturating_sub(2)) {
+                push_indented(&mut help, &line, 2);
+            }
+
+            help
+        })
+        .collect();
+
+    sections.join("\n")
+}
+
+/// Returns a section with its rows aligned in two columns, or nothing without rows.
+///
+/// Descriptions wrap under their column to fit `width` columns, when the column would be
+/// narrower than [`MIN_DESCRIPTION_WIDTH`] they go below the names instead.
+fn section(heading: &str, rows: &[Row], styles: &Styles, width: usize) -> String {
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let left_width = rows.iter().map(|row| row.width).max().unwrap_or(0);
+    let indent = 2 + left_width + 2;
+    let next_line = width.saturating_sub(indent) < MIN_DESCRIPTION_WIDTH;
+
+    let mut help = format!("{}\n", styles.header.paint(&format!("{}:", heading)));
+
+    for row in rows {
+        if row.right.is_empty() {
+            help.push_str(&format!("  {}\n", row.left));
+        } else if next_line {
+            help.push_str(&format!("  {}\n", row.left));
+
+            for line in wrap(&row.right, width.saturating_sub(NEXT_LINE_INDENT)) {
+                push_indented(&mut help, &line, NEXT_LINE_INDENT);
+            }
+        } else {
+            let lines = wrap(&row.right, width - indent);
+            let padding = " ".repeat(left_width - row.width);
+
+            help.push_str(&format!("  {}{}  {}\n", row.left, padding, lines[0]));
+
+            for line in &lines[1..] {
+                push_indented(&mut help, line, indent);
+            }
+        }
+    }
+
+    help
+}
+
+/// Pushes `line` after `indent` spaces, blank lines get no spaces.
+fn push_indented(help: &mut String, line: &str, indent: usize) {
+    if !line.is_empty() {
+        help.push_str(&" ".repeat(indent));
+        help.push_str(line);
+    }
+    help.push('\n');
+}
+
+/// Returns the row of a flag, its left column is like `-r, --release` or `    --jobs <VALUE>`.
+pub(crate) fn flag_row(flag: &Flag, styles: &Styles, long: bool) -> Row {
+    let short = match flag.short {
+        Some(short) => format!("-{}", short),
+        None => String::new(),
+    };
+
+    let name = if flag.negatable {
+        format!("--[no-]{}", flag.long)
+    } else {
+        format!("--{}", flag.long)
+    };
+
+    let (separator, value) = if flag.key_value {
+        (" ", "<KEY=VALUE>")
+    } else if flag.optional_value {
+        ("", "[=<VALUE>]")
+    } else if flag.takes_value {
+        (" ", "<VALUE>")
+    } else {
+        ("", "")
+    };
+
+    let (left, indent) = if short.is_empty() {
+        (format!("    {}", styles.literal.paint(&name)), 4)
+    } else {
+        let left = format!(
+            "{}, {}",
+            styles.literal.paint(&short),
+            styles.literal.paint(&name)
+        );
+        (left, display_width(&short) + 2)
+    };
+
+    Row {
+        left: format!("{}{}{}", left, separator, styles.placeholder.paint(value)),
+        width: indent + display_width(&name) + separator.len() + value.len(),
+        right: flag_description(flag, long),
+    }
+}
+
+/// Returns the flag description followed by its visible aliases and labels.
+pub(crate) fn flag_description(flag: &Flag, long: bool) -> String {
+    let aliases: Vec<String> = flag
+        .get_visible_short_aliases()
+        .map(|alias| format!("-{}", alias))
+        .chain(
+            flag.get_visible_aliases()
+                .map(|alias| format!("--{}", alias)),
+        )
+        .collect();
+
+    let description = flag.get_help_description(long).unwrap_or_default();
+
+    let description = match (description.is_empty(), aliases.is_empty()) {
+        (_, true) => description.to_string(),
+        (true, false) => format!("[aliases: {}]", aliases.join(", ")),
+        (false, false) => format!("{} [aliases: {}]", description, aliases.join(", ")),
+    };
+
+    labeled(description, flag.deprecated.is_some(), flag.experimental)
+}
+
+/// Returns `description` followed by `[deprecated]` and `[experimental]` labels.
+fn labeled(mut description: String, deprecated: bool, experimental: bool) -> String {
+    let labels = [
+        (deprecated, "[deprecated]"),
+        (experimental, "[experimental]"),
+    ];
+
+    for (_, label) in labels.iter().filter(|(set, _)| *set) {
+        if !description.is_empty() {
+            description.push(' ');
+        }
+        description.push_str(label);
+    }
+
+    description
+}
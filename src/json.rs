@@ -0,0 +1,47 @@
+// A minimal JSON writer for error and parse-result output, so the crate doesn't need serde
+
+/// Returns `s` as a JSON string, with quotes and escapes.
+pub(crate) fn string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// Returns `s` as a JSON string, or `null`.
+pub(crate) fn optional_string(s: Option<&str>) -> String {
+    s.map_or("null".to_string(), string)
+}
+
+/// Returns an array of values that are already JSON.
+pub(crate) fn array<I: IntoIterator<Item = String>>(values: I) -> String {
+    format!("[{}]", values.into_iter().collect::<Vec<_>>().join(","))
+}
+
+/// Returns an array of JSON strings.
+pub(crate) fn strings<'a, I: IntoIterator<Item = &'a str>>(values: I) -> String {
+    array(values.into_iter().map(string))
+}
+
+/// Returns an object of keys and values that are already JSON, in order.
+pub(crate) fn object<'a, I: IntoIterator<Item = (&'a str, String)>>(fields: I) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|(key, value)| format!("{}:{}", string(key), value))
+        .collect();
+
+    format!("{{{}}}", fields.join(","))
+}
@@ -1,3 +1,10 @@
 pub mod builder;
+pub mod dispatch;
+mod docs;
 pub mod errors;
+mod help;
+mod json;
 pub mod parser;
+mod repl;
+pub mod style;
+mod text;
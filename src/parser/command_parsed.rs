@@ -1,5 +1,11 @@
+use std::process::{ExitCode, Termination};
+
 #[allow(unused_imports)]
 use crate::builder::*;
+use crate::errors::ErrorFormat;
+use crate::json;
+use crate::parser::PropertyMap;
+use crate::style::ColorChoice;
 /// Represents a CLI argument broken into simple strings.
 ///
 /// This struct is the output of [`App::run()`] or [`App::try_parse_args()`], providing a simple
@@ -9,7 +15,7 @@ use crate::builder::*;
 /// uses [`std::env::args_os()`] as the CLI input, which removes the need to
 /// manually handle user input.
 /// You can also use [`App::try_run()`] to manually handle errors that may occur during parsing
-/// You can use [`App::parse()`] or [`App::try_parse()`] to manually handle user input.
+/// You can use [`App::parse_from()`] or [`App::try_parse_from()`] to manually handle user input.
 ///
 /// # Example:
 /// ```rust,no_run
@@ -58,9 +64,32 @@ use crate::builder::*;
 /// assert_eq!(cli.get_values().any(|f| f == "8080"), true);
 /// ```
 pub struct CommandParsed {
-    pub(crate) command: String,
-    pub(crate) subcommand: Option<String>,
+    /// The command followed by every subcommand, never empty.
+    pub(crate) path: Vec<String>,
     pub(crate) flags: Vec<String>,
+    /// The final state of every flag, with the index in the path of the command that owns it.
+    /// A flag of a subcommand shadows the flag of its parent with the same name.
+    pub(crate) states: Vec<(String, usize, bool)>,
+    pub(crate) occurrences: Vec<Occurrence>,
+    pub(crate) properties: Vec<(String, PropertyMap)>,
+    pub(crate) values: Vec<String>,
+    pub(crate) unknown: Vec<String>,
+    pub(crate) warnings: Vec<String>,
+    pub(crate) error_format: ErrorFormat,
+    pub(crate) color: ColorChoice,
+}
+
+/// A single occurrence of a flag in the CLI input.
+#[derive(Debug, Clone)]
+pub(crate) struct Occurrence {
+    /// Long name of the flag, even if it was given by its short name or an alias.
+    pub(crate) long: String,
+    /// Index in the command path of the command the flag belongs to.
+    pub(crate) command: usize,
+    /// Index of the flag in the CLI input.
+    pub(crate) index: usize,
+    /// `false` for `--no-<flag>`.
+    pub(crate) state: bool,
     pub(crate) values: Vec<String>,
 }
 
@@ -71,7 +100,7 @@ impl CommandParsed {
     ///
     /// For `cargo run`, returns `"cargo"`.
     pub fn get_command(&self) -> &str {
-        &self.command
+        self.path.first().map_or("", |s| s.as_str())
     }
 
     /// Returns the subcommand of the CLI input.
@@ -81,12 +110,23 @@ impl CommandParsed {
     /// For `cargo run`, returns `Some("run")`.
     /// For `cargo`, returns `"None"`.   
     pub fn get_subcommand(&self) -> Option<&str> {
-        self.subcommand.as_deref()
+        self.path.get(1).map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over the command and every nested subcommand of the CLI input.
+    ///
+    /// # Example:
+    ///
+    /// For `cargo help build -v`, this returns an iterator over:
+    /// ["cargo", "help", "build"]
+    pub fn get_command_path(&self) -> impl Iterator<Item = &str> {
+        self.path.iter().map(|s| s.as_str())
     }
 
     /// Returns an iterator over the flags in the CLI input.
     ///
-    /// Flags are arguments that start with `-` or `--`.
+    /// Flags are arguments that start with `-` or `--`. Each flag is returned once, by its long
+    /// name, and flags turned off by a later `--no-<flag>` aren't returned.
     ///
     /// # Example:
     ///
@@ -96,9 +136,94 @@ impl CommandParsed {
         self.flags.iter().map(|s| s.as_str())
     }
 
+    /// Returns the final state of a flag of the parsed command, or `None` if the command has no
+    /// flag called `long`.
+    ///
+    /// The last occurrence of a flag wins, flags that don't occur return their
+    /// [`Flag::enabled_by_default()`] state.
+    ///
+    /// # Example:
+    ///
+    /// For `ls --color --no-color`, `get_flag_state("color")` returns `Some(false)`.
+    pub fn get_flag_state(&self, long: &str) -> Option<bool> {
+        self.states
+            .iter()
+            .find(|(name, ..)| name == long)
+            .map(|(.., state)| *state)
+    }
+
+    /// Returns how many times a flag occurs in the CLI input, `--no-<flag>` isn't counted.
+    ///
+    /// Like for [`CommandParsed::get_flag_state()`], a flag of a subcommand shadows the flag of
+    /// its parent with the same name. The same goes for the indices and values of a flag.
+    pub fn get_flag_count(&self, long: &str) -> usize {
+        self.get_flag_indices(long).count()
+    }
+
+    /// Returns an iterator over the index of every occurrence of a flag in the CLI input, where
+    /// `0` is the binary name, or the command if [`App::no_binary_name()`] is set.
+    ///
+    /// # Example:
+    ///
+    /// For `gcc cc build -I src -I include`, `get_flag_indices("include")` returns an iterator
+    /// over: [3, 5]
+    pub fn get_flag_indices(&self, long: &str) -> impl Iterator<Item = usize> {
+        self.enabling(long).map(|occurrence| occurrence.index)
+    }
+
+    /// Returns the last value given to a flag.
+    ///
+    /// # Example:
+    ///
+    /// For `app run --log --log=debug`, `get_flag_value("log")` returns `Some("debug")`.
+    pub fn get_flag_value(&self, long: &str) -> Option<&str> {
+        self.get_flag_values(long).last()
+    }
+
+    /// Returns an iterator over every value given to a flag, in order.
+    ///
+    /// # Example:
+    ///
+    /// For `gcc cc build -I src -I include --tags a,b`, with `,` as the `tags` value delimiter,
+    /// `get_flag_values("include")` returns an iterator over ["src", "include"] and
+    /// `get_flag_values("tags")` returns an iterator over ["a", "b"].
+    pub fn get_flag_values(&self, long: &str) -> impl Iterator<Item = &str> {
+        self.enabling(long)
+            .flat_map(|occurrence| occurrence.values.iter().map(|s| s.as_str()))
+    }
+
+    /// Returns the occurrences that turn a flag on, in the command whose flag
+    /// [`CommandParsed::get_flag_state()`] returns.
+    fn enabling(&self, long: &str) -> impl Iterator<Item = &Occurrence> {
+        let command = self
+            .states
+            .iter()
+            .find(|(name, ..)| name == long)
+            .map(|(_, command, _)| *command);
+
+        self.occurrences.iter().filter(move |occurrence| {
+            Some(occurrence.command) == command && occurrence.long == long && occurrence.state
+        })
+    }
+
+    /// Returns the `key=value` pairs given to a [`Flag::key_value()`] flag, or `None` if the
+    /// command has no such flag.
+    ///
+    /// # Example:
+    ///
+    /// For `mvn package -D env=prod --define skip.tests=true`, `get_properties("define")`
+    /// returns a map of `env` to `prod` and `skip.tests` to `true`.
+    pub fn get_properties(&self, long: &str) -> Option<&PropertyMap> {
+        self.properties
+            .iter()
+            .find(|(name, _)| name == long)
+            .map(|(_, map)| map)
+    }
+
     /// Returns an iterator over the values in the CLI input.
     ///
     /// Values are usually non-flags strings, often arguments to flags
+    /// Every arg after `--` is a value, even if it starts with `-`.
     ///
     /// # Example:
     ///
@@ -107,4 +232,133 @@ impl CommandParsed {
     pub fn get_values(&self) -> impl Iterator<Item = &str> {
         self.values.iter().map(|s| s.as_str())
     }
+
+    /// Returns an iterator over the flags that don't match any flag of the command, in the order
+    /// they are given.
+    ///
+    /// This is always empty unless [`App::allow_unknown()`] is set.
+    ///
+    /// # Example:
+    ///
+    /// For `wrap run -v --foo=bar -x`, where `run` only has `-v`, this returns an iterator over:
+    /// ["--foo=bar", "-x"]
+    pub fn get_unknown_flags(&self) -> impl Iterator<Item = &str> {
+        self.unknown.iter().map(|s| s.as_str())
+    }
+
+    /// Returns an iterator over the warnings of the deprecated commands and flags used, in the
+    /// order they were found.
+    ///
+    /// [`App::parse_from()`] and the methods built on it print them to stderr, the `try_*`
+    /// methods leave them to the caller.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo").flag(Flag::new("frozen").deprecated("use `--locked` instead")),
+    /// );
+    /// let cli = app.try_parse_from(["rust", "cargo", "--frozen"]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     cli.get_warnings().collect::<Vec<_>>(),
+    ///     ["`--frozen` is deprecated, use `--locked` instead"]
+    /// );
+    /// ```
+    pub fn get_warnings(&self) -> impl Iterator<Item = &str> {
+        self.warnings.iter().map(|s| s.as_str())
+    }
+
+    /// Returns the format picked for this parse: the built-in `--error-format` flag if given,
+    /// otherwise [`App::error_format()`]. Handlers can use it to print their own errors.
+    pub fn get_error_format(&self) -> ErrorFormat {
+        self.error_format
+    }
+
+    /// Returns the color choice picked for this parse: the built-in `--color` flag if given,
+    /// otherwise [`App::color()`].
+    pub fn get_color(&self) -> ColorChoice {
+        self.color
+    }
+
+    /// Returns the parse result as a JSON object, for scripts and editors:
+    ///
+    /// ```json
+    /// {
+    ///   "path": ["mvn", "package"],
+    ///   "flags": ["define"],
+    ///   "states": {"define": true, "quiet": false},
+    ///   "occurrences": [{"flag": "define", "index": 3, "state": true, "values": ["env=prod"]}],
+    ///   "properties": {"define": {"env": ["prod"]}},
+    ///   "values": ["app"],
+    ///   "unknown": [],
+    ///   "warnings": []
+    /// }
+    /// ```
+    ///
+    /// Fields mirror the getters of [`CommandParsed`]. New fields may be added, existing fields
+    /// won't change.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(Command::new("cargo").flag(Flag::new("release")));
+    /// let cli = app.try_parse_from(["rust", "cargo", "--release", "ecp"]).unwrap();
+    ///
+    /// assert_eq!(
+    ///     cli.to_json(),
+    ///     r#"{"path":["cargo"],"flags":["release"],"states":{"release":true},"occurrences":[{"flag":"release","index":2,"state":true,"values":[]}],"properties":{},"values":["ecp"],"unknown":[],"warnings":[]}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let states = self
+            .states
+            .iter()
+            .map(|(long, _, state)| (long.as_str(), state.to_string()));
+
+        let occurrences = self.occurrences.iter().map(|occurrence| {
+            json::object([
+                ("flag", json::string(&occurrence.long)),
+                ("index", occurrence.index.to_string()),
+                ("state", occurrence.state.to_string()),
+                (
+                    "values",
+                    json::strings(occurrence.values.iter().map(String::as_str)),
+                ),
+            ])
+        });
+
+        let properties = self.properties.iter().map(|(long, map)| {
+            let entries = map.entries.iter().map(|(key, values)| {
+                (
+                    key.as_str(),
+                    json::strings(values.iter().map(String::as_str)),
+                )
+            });
+
+            (long.as_str(), json::object(entries))
+        });
+
+        json::object([
+            ("path", json::strings(self.get_command_path())),
+            ("flags", json::strings(self.get_flags())),
+            ("states", json::object(states)),
+            ("occurrences", json::array(occurrences)),
+            ("properties", json::object(properties)),
+            ("values", json::strings(self.get_values())),
+            ("unknown", json::strings(self.get_unknown_flags())),
+            ("warnings", json::strings(self.get_warnings())),
+        ])
+    }
+}
+
+/// A successful parse always exits with `0`, so `main` can return the parse result.
+///
+/// See [`App::report()`] to also get the exit codes set with [`App::exit_code()`] on errors.
+impl Termination for CommandParsed {
+    fn report(self) -> ExitCode {
+        ExitCode::SUCCESS
+    }
 }
@@ -1,4 +1,8 @@
 mod command_parsed;
+mod property_map;
+mod shell;
 pub(super) mod utils;
 
 pub use command_parsed::CommandParsed;
+pub use property_map::PropertyMap;
+pub use shell::split_line;
@@ -0,0 +1,56 @@
+/// An ordered map of the `key=value` pairs given to a [`Flag::key_value()`] flag.
+///
+/// Keys are kept in the order they first occur in the CLI input.
+///
+/// [`Flag::key_value()`]: crate::builder::Flag::key_value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PropertyMap {
+    pub(crate) entries: Vec<(String, Vec<String>)>,
+}
+
+impl PropertyMap {
+    /// Returns the last value of `key`.
+    ///
+    /// # Example:
+    ///
+    /// For `mvn -D env=dev -D env=prod`, `get("env")` returns `Some("prod")`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.get_all(key).last()
+    }
+
+    /// Returns an iterator over every value of `key`, in order.
+    ///
+    /// Only [`DuplicateKeys::Collect`](crate::builder::DuplicateKeys::Collect) keeps more than
+    /// one value per key.
+    pub fn get_all(&self, key: &str) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .flat_map(|(_, values)| values.iter().map(|s| s.as_str()))
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.iter().any(|(k, _)| k == key)
+    }
+
+    /// Returns an iterator over the keys, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(k, _)| k.as_str())
+    }
+
+    /// Returns an iterator over every `(key, value)` pair, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .flat_map(|(k, values)| values.iter().map(move |v| (k.as_str(), v.as_str())))
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,101 @@
+use crate::errors::AppError;
+
+/// Splits a command line into args following POSIX shell quoting rules.
+///
+/// Args are separated by unquoted whitespace. Single quotes keep every character as is, double
+/// quotes keep every character but a backslash before `"`, `\`, `$`, `` ` `` or a newline.
+/// Outside of quotes a backslash keeps the next character as is, and a backslash before a newline
+/// joins the lines. Empty quotes are an empty arg.
+///
+/// # Errors:
+/// Returns [`AppError::InvalidInput`] for an unterminated quote or a trailing backslash.
+///
+/// # Example:
+/// ```
+/// use ecp::parser::split_line;
+///
+/// let args = split_line(r#"deploy web --env "staging eu" --tag 'v1 "rc"' a\ b"#).unwrap();
+///
+/// assert_eq!(
+///     args,
+///     vec!["deploy", "web", "--env", "staging eu", "--tag", "v1 \"rc\"", "a b"]
+/// );
+/// ```
+pub fn split_line(line: &str) -> Result<Vec<String>, AppError> {
+    let mut args = Vec::new();
+    // `None` between args, an arg can be empty if it only has quotes
+    let mut arg: Option<String> = None;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(arg) = arg.take() {
+                    args.push(arg);
+                }
+            }
+            '\\' => match chars.next() {
+                Some((_, '\n')) => {}
+                Some((_, escaped)) => arg.get_or_insert_default().push(escaped),
+                None => {
+                    return Err(AppError::InvalidInput(format!(
+                        "Trailing backslash at column {}",
+                        column(line, position)
+                    )));
+                }
+            },
+            '\'' => {
+                let arg = arg.get_or_insert_default();
+
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => arg.push(c),
+                        None => return Err(unterminated(line, '\'', position)),
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_default();
+
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.peek() {
+                            Some((_, '\n')) => {
+                                chars.next();
+                            }
+                            Some((_, escaped @ ('"' | '\\' | '$' | '`'))) => {
+                                arg.push(*escaped);
+                                chars.next();
+                            }
+                            _ => arg.push('\\'),
+                        },
+                        Some((_, c)) => arg.push(c),
+                        None => return Err(unterminated(line, '"', position)),
+                    }
+                }
+            }
+            c => arg.get_or_insert_default().push(c),
+        }
+    }
+
+    if let Some(arg) = arg {
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+fn unterminated(line: &str, quote: char, position: usize) -> AppError {
+    AppError::InvalidInput(format!(
+        "Unterminated {} quote starting at column {}",
+        quote,
+        column(line, position)
+    ))
+}
+
+/// Returns the 1-based column of the character at byte `position`.
+fn column(line: &str, position: usize) -> usize {
+    line[..position].chars().count() + 1
+}
@@ -1,87 +1,898 @@
 use crate::builder::*;
-use crate::errors::AppError;
+use crate::errors::{AppError, Diagnostic, ErrorFormat};
+use crate::help::render_path;
+use crate::parser::command_parsed::Occurrence;
+use crate::parser::{CommandParsed, PropertyMap};
+use crate::style::{ColorChoice, Styles};
 
-pub fn get_command(app: &App, args: &[String]) -> Result<String, AppError> {
-    let command_name = &args[1];
+/// Where the parser is in the args.
+///
+/// The parser only moves forward: from the command, through its subcommands, to the arguments of
+/// the deepest command found.
+enum State<'a> {
+    /// Expects a top-level command.
+    Command,
+    /// Expects a subcommand or a flag of the command.
+    Subcommand(&'a Command),
+    /// Expects a flag or a value of the command.
+    Arguments(&'a Command),
+    /// After `--`, every arg is a value.
+    Values,
+}
 
-    if !app.commands.iter().any(|cmd| cmd.name == *command_name) {
-        return Err(AppError::InvalidCommand(format!(
-            "Command not found: {}",
-            command_name
-        )));
+/// A single arg, classified in the scope of a command.
+enum Token<'a> {
+    /// `--name` or `--name=value`.
+    Long(&'a str, Option<&'a str>),
+    /// `-c` or a cluster of short flags like `-la` and `-j4`, without the `-`.
+    Short(&'a str),
+    /// Anything that isn't a flag, including a lone `-` and negative numbers.
+    Value(&'a str),
+}
+
+/// Parses the args into a [`CommandParsed`], `args[0]` is skipped as the binary name unless
+/// [`App::no_binary_name()`] is set.
+///
+/// This never panics, any arg that doesn't fit the [`App`] is returned as a [`Diagnostic`] of
+/// where it happened in the args.
+pub fn parse(app: &App, args: &[String]) -> Result<CommandParsed, Vec<Diagnostic>> {
+    let mut parser = Parser {
+        app,
+        args,
+        index: if app.no_binary_name { 0 } else { 1 },
+        path: Vec::new(),
+        occurrences: Vec::new(),
+        values: Vec::new(),
+        unknown: Vec::new(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        experimental: Vec::new(),
+        unstable: false,
+        error_format: app.error_format,
+        color: app.color,
+        styled_help: None,
+    };
+
+    let result = parser.run();
+    let (error_format, color) = (parser.error_format, parser.color);
+
+    let result = match result {
+        Ok(()) => parser.finish(),
+        Err(error) => Err(parser.fail(error)),
     };
 
-    Ok(command_name.to_string())
+    // Errors kept before the built-in flags are printed like the rest
+    result.map_err(|mut diagnostics| {
+        for diagnostic in &mut diagnostics {
+            diagnostic.error_format = error_format;
+            diagnostic.color = color;
+        }
+
+        diagnostics
+    })
 }
 
-pub fn get_subcommand(app: &App, args: &[String]) -> Result<Option<String>, AppError> {
-    let subcommand_name = if let Some(subcmd) = args.get(2) {
-        subcmd
+/// Returns the error of the diagnostics returned by [`parse()`], every error is kept in [`AppError::Multiple`] if
+/// [`App::collect_errors()`] is set.
+pub fn into_error(app: &App, mut diagnostics: Vec<Diagnostic>) -> AppError {
+    if app.collect_errors && !diagnostics.iter().any(|d| d.error.is_display()) {
+        let errors = diagnostics
+            .into_iter()
+            .map(Diagnostic::into_error)
+            .collect();
+        return AppError::Multiple(errors);
+    }
+
+    diagnostics
+        .pop()
+        .map_or(AppError::Unknown, Diagnostic::into_error)
+}
+
+/// Classifies `arg` in the scope of `scope`.
+fn tokenize<'a>(scope: &Command, arg: &'a str) -> Token<'a> {
+    if let Some(long) = arg.strip_prefix("--") {
+        match long.split_once('=') {
+            Some((name, value)) => Token::Long(name, Some(value)),
+            None => Token::Long(long, None),
+        }
+    } else if let Some(cluster) = arg.strip_prefix('-')
+        && !is_hyphen_value(scope, arg)
+    {
+        Token::Short(cluster)
     } else {
-        return Ok(None);
-    };
+        Token::Value(arg)
+    }
+}
+
+struct Parser<'a> {
+    app: &'a App,
+    args: &'a [String],
+    /// Index of the arg being parsed.
+    index: usize,
+    /// The command and every subcommand found, in order.
+    path: Vec<&'a Command>,
+    occurrences: Vec<Occurrence>,
+    values: Vec<String>,
+    unknown: Vec<String>,
+    /// Errors kept by [`App::collect_errors()`].
+    errors: Vec<Diagnostic>,
+    /// Warnings of the deprecated commands and flags used.
+    warnings: Vec<String>,
+    /// The experimental commands and flags used, with the index of their first use.
+    experimental: Vec<(String, usize)>,
+    /// Set by the built-in flag of [`App::unstable_flag()`].
+    unstable: bool,
+    /// Set by the built-in `--error-format` flag, [`App::error_format()`] otherwise.
+    error_format: ErrorFormat,
+    /// Set by the built-in `--color` flag, [`App::color()`] otherwise.
+    color: ColorChoice,
+    /// The help requested, painted with [`App::styles()`] unless the color is never used.
+    styled_help: Option<String>,
+}
+
+impl<'a> Parser<'a> {
+    /// Walks the args from the command to its arguments.
+    fn run(&mut self) -> Result<(), AppError> {
+        let app = self.app;
+        let mut state = State::Command;
+
+        while let Some(arg) = self.args.get(self.index) {
+            state = match state {
+                State::Command => match self.builtin(arg) {
+                    Some(result) => {
+                        self.recover(result)?;
+                        State::Command
+                    }
+                    None => {
+                        let cmd = find_command(app, &app.commands, arg)?.ok_or_else(|| {
+                            AppError::InvalidCommand(format!("Command not found: {}", arg))
+                        })?;
+
+                        self.enter(cmd)
+                    }
+                },
+                // `--` ends the flags of the command
+                State::Subcommand(_) | State::Arguments(_) if arg == "--" => State::Values,
+                State::Subcommand(scope) => match tokenize(scope, arg) {
+                    // The first value of a command with subcommands must be one of them
+                    Token::Value(name) => {
+                        let subcmd =
+                            find_command(app, &scope.subcommands, name)?.ok_or_else(|| {
+                                AppError::InvalidCommand(format!("Subcommand not found: {}", name))
+                            })?;
+
+                        self.enter(subcmd)
+                    }
+                    token => {
+                        let result = self.argument(scope, arg, token);
+                        self.recover(result)?;
+                        State::Subcommand(scope)
+                    }
+                },
+                State::Arguments(scope) => {
+                    let result = self.argument(scope, arg, tokenize(scope, arg));
+                    self.recover(result)?;
+                    State::Arguments(scope)
+                }
+                State::Values => {
+                    self.values.push(arg.clone());
+                    State::Values
+                }
+            };
+
+            self.index += 1;
+        }
+
+        if self.path.is_empty() {
+            return Err(AppError::InvalidInput("Too few arguments".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the error of `result` if [`App::collect_errors()`] is set, so parsing goes on.
+    ///
+    /// Help and version output is never kept.
+    fn recover(&mut self, result: Result<(), AppError>) -> Result<(), AppError> {
+        match result {
+            Err(error) if self.app.collect_errors && !error.is_display() => {
+                let diagnostic = self.locate(error);
+                self.errors.push(diagnostic);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Returns the error that stops parsing, along with every error kept before it.
+    fn fail(mut self, error: AppError) -> Vec<Diagnostic> {
+        let diagnostic = Diagnostic {
+            styled_help: self.styled_help.take(),
+            ..self.locate(error)
+        };
+
+        if !self.app.collect_errors || diagnostic.error.is_display() {
+            return vec![diagnostic];
+        }
+
+        self.errors.push(diagnostic);
+        self.errors
+    }
+
+    /// Returns a [`Diagnostic`] of an error caused by the arg being parsed, with the names close
+    /// to it. Errors found after the last arg have no token.
+    fn locate(&self, error: AppError) -> Diagnostic {
+        let token = self.args.get(self.index).cloned();
+
+        let suggestions = match (&error, token.as_deref()) {
+            (AppError::InvalidFlag(_), Some(token)) => self.similar_flags(token),
+            (AppError::InvalidCommand(_), Some(token)) => self.similar_commands(token),
+            _ => error.candidates(),
+        };
+
+        Diagnostic {
+            error,
+            position: token.as_ref().map(|_| self.index),
+            token,
+            suggestions,
+            error_format: self.error_format,
+            color: self.color,
+            styled_help: None,
+        }
+    }
+
+    /// Returns the long flags of every command found with a name close to `token`, hidden flags
+    /// are never suggested.
+    fn similar_flags(&self, token: &str) -> Vec<String> {
+        let Some(name) = token.strip_prefix("--") else {
+            return Vec::new();
+        };
+        let name = name.split('=').next().unwrap_or(name);
+
+        let mut similar = Vec::new();
+        for flag in self.path.iter().flat_map(|cmd| &cmd.flags) {
+            if !flag.hidden && flag.get_long_names().any(|long| is_similar(name, long)) {
+                similar.push(format!("--{}", flag.long));
+            }
+        }
+
+        similar.dedup();
+        similar
+    }
+
+    /// Returns the commands that could follow the command found, with a name close to `token`.
+    fn similar_commands(&self, token: &str) -> Vec<String> {
+        let commands = self
+            .path
+            .last()
+            .map_or(&self.app.commands, |cmd| &cmd.subcommands);
+
+        commands
+            .iter()
+            .filter(|cmd| !cmd.hidden && cmd.get_names().any(|name| is_similar(token, name)))
+            .map(|cmd| cmd.name.clone())
+            .collect()
+    }
 
-    for cmd in app.commands.iter() {
-        if !cmd.subcommands.iter().any(|subcmd| subcmd.name == args[2]) {
-            return Err(AppError::InvalidCommand(format!(
-                "Subcommand not found: {}",
-                subcommand_name
+    /// Moves into `cmd`, returning the state that follows it.
+    fn enter(&mut self, cmd: &'a Command) -> State<'a> {
+        self.path.push(cmd);
+        self.mark(&cmd.name, cmd.deprecated.as_deref(), cmd.experimental);
+
+        if cmd.subcommands.is_empty() {
+            State::Arguments(cmd)
+        } else {
+            State::Subcommand(cmd)
+        }
+    }
+
+    /// Parses a flag or a value of `scope`.
+    fn argument(&mut self, scope: &'a Command, arg: &str, token: Token) -> Result<(), AppError> {
+        match token {
+            Token::Long(name, attached) => {
+                // A prefix never takes over a built-in flag, only a flag with its exact name
+                let prefixes = self.app.allow_prefixes && !is_builtin(self.app, name, attached);
+
+                match find_long(&scope.flags, name, prefixes)? {
+                    Some((flag, true)) if attached.is_some() && !flag.takes_value => {
+                        Err(AppError::UnexpectedValue(flag.long.clone()))
+                    }
+                    Some((flag, false)) if attached.is_some() => {
+                        Err(AppError::UnexpectedValue(format!("no-{}", flag.long)))
+                    }
+                    Some((flag, state)) => self.occur(scope, flag, state, attached),
+                    None => self.unmatched(scope, arg),
+                }
+            }
+            Token::Short(cluster) => self.cluster(scope, arg, cluster),
+            Token::Value(value) => {
+                self.values.push(value.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// Parses a cluster of short flags, like `-la` or `-j4`.
+    ///
+    /// Every flag that takes no value is set, the rest of the cluster after a flag that takes one
+    /// is its value, after a `=` for an optional value, and any other char starts an unknown flag. A cluster that doesn't start with a
+    /// short flag of `scope` isn't a flag of the command at all, like `-inf`.
+    fn cluster(&mut self, scope: &'a Command, arg: &str, cluster: &str) -> Result<(), AppError> {
+        let starts_with_flag = cluster
+            .chars()
+            .next()
+            .and_then(|c| find_short(&scope.flags, c))
+            .is_some();
+
+        if !starts_with_flag {
+            return self.unmatched(scope, arg);
+        }
+
+        for (i, c) in cluster.char_indices() {
+            let rest = &cluster[i + c.len_utf8()..];
+
+            // The rest of the cluster may be the value of the unknown flag, keep it whole
+            let Some(flag) = find_short(&scope.flags, c) else {
+                return self.unknown(scope, &format!("-{}{}", c, rest));
+            };
+
+            // An optional value can only be attached with `=`, so `-cv` is `-c -v`
+            if flag.optional_value && !rest.starts_with('=') {
+                self.occur(scope, flag, true, None)?;
+                continue;
+            }
+
+            if flag.takes_value {
+                // `-s=value` is the same as `-svalue`
+                let attached = (!rest.is_empty()).then(|| rest.strip_prefix('=').unwrap_or(rest));
+
+                return self.occur(scope, flag, true, attached);
+            }
+
+            if rest.starts_with('=') {
+                return Err(AppError::UnexpectedValue(flag.long.clone()));
+            }
+
+            self.occur(scope, flag, true, None)?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles an arg that starts with `-` but isn't a flag of `scope`: a built-in flag, a value
+    /// of a command that allows hyphen values, an unknown flag kept by [`App::allow_unknown()`]
+    /// or an error.
+    fn unmatched(&mut self, scope: &Command, arg: &str) -> Result<(), AppError> {
+        if let Some(result) = self.builtin(arg) {
+            return result;
+        }
+
+        if scope.allow_hyphen_values {
+            self.values.push(arg.to_string());
+            return Ok(());
+        }
+
+        self.unknown(scope, arg)
+    }
+
+    /// Keeps a flag that isn't a flag of `scope` if [`App::allow_unknown()`] is set, or returns
+    /// an error. `flag` is the whole arg, or the rest of a short cluster from its first unknown
+    /// char.
+    fn unknown(&mut self, scope: &Command, flag: &str) -> Result<(), AppError> {
+        if !self.app.allow_unknown {
+            return Err(AppError::InvalidFlag(format!(
+                "Flag not found: {} for `{}`",
+                flag, scope.name
             )));
         }
+
+        self.unknown.push(flag.to_string());
+        Ok(())
+    }
+
+    /// Adds an occurrence of `flag` setting `state`, with the value `attached` to it with `=` or
+    /// in a short cluster, or else taken from the next arg if the flag takes one.
+    fn occur(
+        &mut self,
+        scope: &Command,
+        flag: &Flag,
+        state: bool,
+        attached: Option<&str>,
+    ) -> Result<(), AppError> {
+        self.mark(
+            &format!("--{}", flag.long),
+            flag.deprecated.as_deref(),
+            flag.experimental,
+        );
+
+        let mut occurrence = Occurrence {
+            long: flag.long.clone(),
+            command: self.path.len().saturating_sub(1),
+            index: self.index,
+            state,
+            values: Vec::new(),
+        };
+
+        if flag.takes_value && state {
+            // Optional values are never taken from the next arg
+            let value = match attached {
+                Some(value) => Some(value),
+                None if flag.optional_value => flag.default_missing_value.as_deref(),
+                None => match self.args.get(self.index + 1) {
+                    Some(next)
+                        if !next.starts_with('-')
+                            || flag.allow_hyphen_values
+                            || is_hyphen_value(scope, next) =>
+                    {
+                        self.index += 1;
+                        Some(next.as_str())
+                    }
+                    _ => return Err(AppError::MissingValue(flag.long.clone())),
+                },
+            };
+
+            if let Some(value) = value {
+                occurrence.values = match flag.value_delimiter {
+                    Some(delimiter) => value.split(delimiter).map(|v| v.to_string()).collect(),
+                    None => vec![value.to_string()],
+                };
+            }
+        }
+
+        self.occurrences.push(occurrence);
+
+        Ok(())
+    }
+
+    /// Keeps the warning of a deprecated command or flag and the use of an experimental one, once
+    /// per `name`.
+    fn mark(&mut self, name: &str, deprecated: Option<&str>, experimental: bool) {
+        if let Some(note) = deprecated {
+            let warning = if note.is_empty() {
+                format!("`{}` is deprecated", name)
+            } else {
+                format!("`{}` is deprecated, {}", name, note)
+            };
+
+            if !self.warnings.contains(&warning) {
+                self.warnings.push(warning);
+            }
+        }
+
+        if experimental && !self.experimental.iter().any(|(n, _)| n == name) {
+            self.experimental.push((name.to_string(), self.index));
+        }
     }
 
-    Ok(Some(subcommand_name.to_string()))
+    /// Returns an error for every experimental command or flag used, unless the unstable gate is
+    /// open. Only the first is returned unless [`App::collect_errors()`] is set.
+    fn check_experimental(&mut self) -> Result<(), Vec<Diagnostic>> {
+        if self.unstable || self.app.is_unstable_env_set() {
+            return Ok(());
+        }
+
+        let gate = match (&self.app.unstable_flag, &self.app.unstable_env) {
+            (Some(flag), Some(var)) => format!(", pass `--{}` or set `{}=1` to use it", flag, var),
+            (Some(flag), None) => format!(", pass `--{}` to use it", flag),
+            (None, Some(var)) => format!(", set `{}=1` to use it", var),
+            (None, None) => String::new(),
+        };
+
+        for (name, index) in std::mem::take(&mut self.experimental) {
+            let diagnostic = Diagnostic {
+                error: AppError::Experimental(format!("`{}` is experimental{}", name, gate)),
+                token: self.args.get(index).cloned(),
+                position: Some(index),
+                suggestions: Vec::new(),
+                error_format: self.error_format,
+                color: self.color,
+                styled_help: None,
+            };
+
+            if !self.app.collect_errors {
+                return Err(vec![diagnostic]);
+            }
+
+            self.errors.push(diagnostic);
+        }
+
+        Ok(())
+    }
+
+    /// Handles a built-in flag that isn't shadowed by a flag of the command, returning `None` if
+    /// `arg` isn't one:
+    /// - `-h` anywhere for the short help, `--help` for the long help.
+    /// - `--version` anywhere, if the app has a version.
+    /// - `--error-format=<text|json>` anywhere, it overrides [`App::error_format()`] for this
+    ///   parse.
+    /// - `--color=<auto|always|never>` anywhere, it overrides [`App::color()`] for this parse.
+    /// - `--<name>` of [`App::unstable_flag()`] anywhere, it opens the unstable gate.
+    fn builtin(&mut self, arg: &str) -> Option<Result<(), AppError>> {
+        if let Some(name) = &self.app.unstable_flag
+            && arg.strip_prefix("--") == Some(name.as_str())
+        {
+            self.unstable = true;
+            return Some(Ok(()));
+        }
+
+        if let Some(color) = arg.strip_prefix("--color=") {
+            let color = match color {
+                "auto" => ColorChoice::Auto,
+                "always" => ColorChoice::Always,
+                "never" => ColorChoice::Never,
+                _ => {
+                    return Some(Err(AppError::InvalidValue(format!(
+                        "`--color` expects `auto`, `always` or `never`, found `{}`",
+                        color
+                    ))));
+                }
+            };
+
+            self.color = color;
+            return Some(Ok(()));
+        }
+
+        if let Some(format) = arg.strip_prefix("--error-format=") {
+            let format = match format {
+                "text" => ErrorFormat::Text,
+                "json" => ErrorFormat::Json,
+                _ => {
+                    return Some(Err(AppError::InvalidValue(format!(
+                        "`--error-format` expects `text` or `json`, found `{}`",
+                        format
+                    ))));
+                }
+            };
+
+            self.error_format = format;
+            return Some(Ok(()));
+        }
+
+        let output = match arg {
+            "-h" | "--help" => {
+                let long = arg == "--help";
+
+                // Whether the help is styled is decided where it's written
+                self.styled_help = (self.color != ColorChoice::Never)
+                    .then(|| render_path(self.app, &self.path, &self.app.styles, long));
+
+                AppError::DisplayHelp(render_path(self.app, &self.path, &Styles::plain(), long))
+            }
+            "--version" => {
+                let version = self.app.version.as_ref()?;
+                AppError::DisplayVersion(format!("{} {}", self.app.name, version))
+            }
+            _ => return None,
+        };
+
+        Some(Err(output))
+    }
+
+    /// Builds the [`CommandParsed`], returning every error found if any.
+    fn finish(mut self) -> Result<CommandParsed, Vec<Diagnostic>> {
+        self.check_experimental()?;
+
+        match self.build() {
+            Ok(parsed) if self.errors.is_empty() => Ok(parsed),
+            Ok(_) => Err(self.errors),
+            Err(error) => Err(self.fail(error)),
+        }
+    }
+
+    /// Checks the flags of every command found and builds the [`CommandParsed`].
+    fn build(&mut self) -> Result<CommandParsed, AppError> {
+        // The final state of the flags of every command, by the command index in the path
+        let mut scoped: Vec<(usize, String, bool)> = Vec::new();
+        let mut states: Vec<(String, usize, bool)> = Vec::new();
+        let mut properties = Vec::new();
+        let all = std::mem::take(&mut self.occurrences);
+
+        // Deepest commands first, so their flags shadow the flags of their parents
+        for (command, scope) in self.path.clone().into_iter().enumerate().rev() {
+            let occurrences: Vec<&Occurrence> = all
+                .iter()
+                .filter(|occurrence| occurrence.command == command)
+                .collect();
+
+            for flag in &scope.flags {
+                let result = check_occurrences(flag, &occurrences);
+                self.recover(result)?;
+            }
+
+            for flag in &scope.flags {
+                // The last occurrence of a flag wins, flags that don't occur keep their default
+                let state = occurrences
+                    .iter()
+                    .rev()
+                    .find(|occurrence| occurrence.long == flag.long)
+                    .map_or(flag.enabled_by_default, |occurrence| occurrence.state);
+
+                scoped.push((command, flag.long.clone(), state));
+
+                if states.iter().any(|(long, ..)| *long == flag.long) {
+                    continue;
+                }
+
+                states.push((flag.long.clone(), command, state));
+
+                if flag.key_value {
+                    match get_properties(flag, &occurrences) {
+                        Ok(map) => properties.push((flag.long.clone(), map)),
+                        Err(error) => self.recover(Err(error))?,
+                    }
+                }
+            }
+        }
+
+        // Flags that were given and weren't turned off by a later `--no-<flag>`
+        let mut flags: Vec<String> = Vec::new();
+        for occurrence in &all {
+            let long = &occurrence.long;
+            let enabled = scoped.iter().any(|(command, name, state)| {
+                *command == occurrence.command && name == long && *state
+            });
+
+            if enabled && !flags.contains(long) {
+                flags.push(long.clone());
+            }
+        }
+
+        Ok(CommandParsed {
+            path: self.path.iter().map(|cmd| cmd.name.clone()).collect(),
+            flags,
+            states,
+            occurrences: all,
+            properties,
+            values: std::mem::take(&mut self.values),
+            unknown: std::mem::take(&mut self.unknown),
+            warnings: std::mem::take(&mut self.warnings),
+            error_format: self.error_format,
+            color: self.color,
+        })
+    }
 }
 
-pub fn get_flags(app: &App, args: &[String]) -> Result<Vec<String>, AppError> {
-    let command_name = &args[1];
-    let subcommand_name = &args[2];
-    let command = app.commands.iter().find(|cmd| cmd.name == *command_name);
+/// Returns the [`PropertyMap`] of a [`Flag::key_value()`] flag, from the occurrences of the
+/// command that owns it.
+fn get_properties(flag: &Flag, occurrences: &[&Occurrence]) -> Result<PropertyMap, AppError> {
+    let mut map = PropertyMap::default();
 
-    // Searches for a subcommand that matches args[2], if don't find any the scope will be the main command
-    let scope = match command
-        .unwrap()
-        .subcommands
+    let values = occurrences
         .iter()
-        .find(|subcmd| subcmd.name == *subcommand_name)
-    {
-        Some(subcmd) => subcmd,
-        None => command.unwrap(),
-    };
+        .filter(|occurrence| occurrence.long == flag.long)
+        .flat_map(|occurrence| occurrence.values.iter());
 
-    let mut found_flags = Vec::new();
+    for value in values {
+        let Some((key, value)) = value.split_once('=') else {
+            return Err(AppError::InvalidValue(format!(
+                "`--{}` expects `key=value`, found `{}`",
+                flag.long, value
+            )));
+        };
 
-    for arg in args {
-        if arg.starts_with('-') {
-            let arg = arg.trim_start_matches('-');
+        if !is_valid_key(key) {
+            return Err(AppError::InvalidValue(format!(
+                "`{}` is not a valid key for `--{}`",
+                key, flag.long
+            )));
+        }
 
-            if let Some(flag) = scope
-                .flags
-                .iter()
-                .find(|flag| flag.long == arg || flag.short == arg.chars().next())
-            {
-                found_flags.push(flag);
-            };
+        match map.entries.iter_mut().find(|(k, _)| k == key) {
+            None => map.entries.push((key.to_string(), vec![value.to_string()])),
+            Some(_) if flag.duplicate_keys == DuplicateKeys::Error => {
+                return Err(AppError::DuplicateKey(format!(
+                    "`{}` is given more than once to `--{}`",
+                    key, flag.long
+                )));
+            }
+            Some((_, values)) if flag.duplicate_keys == DuplicateKeys::LastWins => {
+                *values = vec![value.to_string()];
+            }
+            Some((_, values)) => values.push(value.to_string()),
         }
     }
 
-    if found_flags.is_empty() {
-        return Err(AppError::InvalidFlag("Flags not found".to_string()));
+    Ok(map)
+}
+
+/// Keys must be non-empty and can only contain alphanumeric characters, `.`, `_` and `-`.
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Checks that `flag` occurs within its minimum and maximum number of times in `occurrences`,
+/// the occurrences of the command that owns it.
+///
+/// `--no-<flag>` occurrences aren't counted.
+fn check_occurrences(flag: &Flag, occurrences: &[&Occurrence]) -> Result<(), AppError> {
+    let count = occurrences
+        .iter()
+        .filter(|occurrence| occurrence.long == flag.long && occurrence.state)
+        .count();
+
+    if count < flag.min_occurrences {
+        return Err(AppError::InvalidOccurrences(format!(
+            "`--{}` must occur at least {} time(s), found {}",
+            flag.long, flag.min_occurrences, count
+        )));
+    }
+
+    if let Some(max) = flag.max_occurrences.filter(|max| count > *max) {
+        return Err(AppError::InvalidOccurrences(format!(
+            "`--{}` can occur at most {} time(s), found {}",
+            flag.long, max, count
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if `arg` starts with `-` but is a value: a lone `-` or a negative number that
+/// doesn't match any short flag of `scope`.
+fn is_hyphen_value(scope: &Command, arg: &str) -> bool {
+    let Some(number) = arg.strip_prefix('-') else {
+        return false;
     };
 
-    Ok(found_flags.iter().map(|flag| flag.long.clone()).collect())
+    if number.is_empty() {
+        return true;
+    }
+
+    let is_number = number.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && number.parse::<f64>().is_ok();
+
+    is_number
+        && number
+            .chars()
+            .next()
+            .and_then(|c| find_short(&scope.flags, c))
+            .is_none()
 }
 
-pub fn get_values(
-    args: &[String],
-    subcommand: &Option<String>,
-    flags: &[String],
-) -> Result<Vec<String>, AppError> {
-    let skip_count = if let Some(_) = subcommand {
-        3 + flags.len()
-    } else {
-        2 + flags.len()
+/// Finds the command called `name`, by its name or by one of its aliases.
+///
+/// If [`App::allow_prefixes()`] is set, an unambiguous prefix of any of those is accepted too.
+fn find_command<'a>(
+    app: &App,
+    commands: &'a [Command],
+    name: &str,
+) -> Result<Option<&'a Command>, AppError> {
+    if let Some(cmd) = commands
+        .iter()
+        .find(|cmd| cmd.get_names().any(|n| n == name))
+    {
+        return Ok(Some(cmd));
+    }
+
+    if !app.allow_prefixes || name.is_empty() {
+        return Ok(None);
+    }
+
+    let candidates: Vec<&Command> = commands
+        .iter()
+        .filter(|cmd| cmd.get_names().any(|n| n.starts_with(name)))
+        .collect();
+
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [cmd] => Ok(Some(cmd)),
+        _ => Err(AppError::AmbiguousCommand(
+            name.to_string(),
+            candidates.iter().map(|cmd| cmd.name.clone()).collect(),
+        )),
+    }
+}
+
+/// Finds the flag called `--long` and the state it sets.
+///
+/// `--no-<long>` turns off a [`Flag::negatable()`] flag, unless there's a flag called `no-<long>`.
+fn find_long<'a>(
+    flags: &'a [Flag],
+    long: &str,
+    prefixes: bool,
+) -> Result<Option<(&'a Flag, bool)>, AppError> {
+    if let Some(flag) = find_flag(flags.iter(), long, prefixes)? {
+        return Ok(Some((flag, true)));
+    }
+
+    let Some(negated) = long.strip_prefix("no-") else {
+        return Ok(None);
     };
 
-    Ok(args.iter().skip(skip_count).map(|s| s.to_owned()).collect())
+    let negatable = flags.iter().filter(|flag| flag.negatable);
+
+    Ok(find_flag(negatable, negated, prefixes)?.map(|flag| (flag, false)))
+}
+
+/// Finds the flag called `--long`, by its long name or by one of its long aliases.
+///
+/// If `prefixes` is set, from [`App::allow_prefixes()`], an unambiguous prefix of any of those is
+/// accepted too.
+fn find_flag<'a>(
+    flags: impl Iterator<Item = &'a Flag> + Clone,
+    long: &str,
+    prefixes: bool,
+) -> Result<Option<&'a Flag>, AppError> {
+    if let Some(flag) = flags
+        .clone()
+        .find(|flag| flag.get_long_names().any(|n| n == long))
+    {
+        return Ok(Some(flag));
+    }
+
+    if !prefixes || long.is_empty() {
+        return Ok(None);
+    }
+
+    let candidates: Vec<&Flag> = flags
+        .filter(|flag| flag.get_long_names().any(|n| n.starts_with(long)))
+        .collect();
+
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [flag] => Ok(Some(flag)),
+        _ => Err(AppError::AmbiguousFlag(
+            long.to_string(),
+            candidates.iter().map(|flag| flag.long.clone()).collect(),
+        )),
+    }
+}
+
+/// Returns `true` if `--long`, with a value if `attached` is set, is one of the built-in flags
+/// handled by [`Parser::builtin()`].
+fn is_builtin(app: &App, long: &str, attached: Option<&str>) -> bool {
+    match long {
+        "help" => attached.is_none(),
+        "version" => attached.is_none() && app.version.is_some(),
+        "color" | "error-format" => attached.is_some(),
+        _ => attached.is_none() && app.unstable_flag.as_deref() == Some(long),
+    }
+}
+
+/// Finds the flag called `-short`, by its short name or by one of its short aliases.
+fn find_short(flags: &[Flag], short: char) -> Option<&Flag> {
+    flags
+        .iter()
+        .find(|flag| flag.get_short_names().any(|c| c == short))
+}
+
+/// Returns `true` if `name` is a likely typo of `candidate`.
+fn is_similar(name: &str, candidate: &str) -> bool {
+    let max = (candidate.chars().count() / 3).max(1);
+    name != candidate && edit_distance(name, candidate) <= max
+}
+
+/// Returns the edit distance between `a` and `b`, in chars, where swapping two adjacent chars
+/// counts as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // distances[i][j] is the distance between the first `i` chars of `a` and `j` chars of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            let mut distance = (distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1)
+                .min(distances[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[a.len()][b.len()]
 }
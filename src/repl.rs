@@ -0,0 +1,229 @@
+use std::io::{BufRead, Write};
+
+use crate::builder::{App, Command};
+use crate::errors::AppError;
+use crate::parser::{CommandParsed, split_line};
+
+impl App {
+    /// Returns the completions of the last word of `line`, in definition order.
+    ///
+    /// Commands and subcommands are completed by name, flags of every command in the line are
+    /// completed by long name when the word starts with `-`, including `--no-<flag>` for
+    /// [`Flag::negatable()`](crate::builder::Flag::negatable) flags. Hidden commands and flags
+    /// aren't completed. A line ending with a space completes a new word.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    ///
+    /// let app = App::new("Rust").command(
+    ///     Command::new("cargo")
+    ///         .subcommand(Command::new("build").flag(Flag::new("release")))
+    ///         .subcommand(Command::new("bench")),
+    /// );
+    ///
+    /// assert_eq!(app.complete("cargo b"), ["build", "bench"]);
+    /// assert_eq!(app.complete("cargo build --r"), ["--release"]);
+    /// ```
+    pub fn complete(&self, line: &str) -> Vec<String> {
+        let Ok(mut words) = split_line(line) else {
+            return Vec::new();
+        };
+
+        // The word being completed, empty after a trailing space
+        let partial = if line.ends_with(char::is_whitespace) {
+            String::new()
+        } else {
+            words.pop().unwrap_or_default()
+        };
+
+        let mut commands: &[Command] = &self.commands;
+        let mut path: Vec<&Command> = Vec::new();
+
+        for word in words.iter().filter(|word| !word.starts_with('-')) {
+            match commands
+                .iter()
+                .find(|cmd| cmd.get_names().any(|n| n == word))
+            {
+                Some(cmd) => {
+                    path.push(cmd);
+                    commands = &cmd.subcommands;
+                }
+                None if path.is_empty() => return Vec::new(),
+                // A value, nothing after it can be a subcommand
+                None => commands = &[],
+            }
+        }
+
+        let names: Vec<String> = if partial.starts_with('-') {
+            path.iter()
+                .flat_map(|cmd| cmd.flags.iter())
+                .filter(|flag| !flag.hidden)
+                .flat_map(|flag| {
+                    let negated = flag.negatable.then(|| format!("--no-{}", flag.long));
+                    std::iter::once(format!("--{}", flag.long)).chain(negated)
+                })
+                .collect()
+        } else {
+            commands
+                .iter()
+                .filter(|cmd| !cmd.hidden)
+                .map(|cmd| cmd.name.clone())
+                .collect()
+        };
+
+        let mut completions: Vec<String> = Vec::new();
+        for name in names {
+            if name.starts_with(&partial) && !completions.contains(&name) {
+                completions.push(name);
+            }
+        }
+
+        completions
+    }
+
+    /// Runs an interactive loop that reads lines from `input`, parses each of them like
+    /// [`App::try_parse_line()`] and hands the [`CommandParsed`] to `callback`.
+    ///
+    /// The prompt is the app name followed by `> `. Parse and callback errors, and the warnings of
+    /// deprecated commands and flags, are written to `output` and the loop goes on, it ends on
+    /// `exit`, `quit` or the end of `input`.
+    ///
+    /// Built-in commands, shadowed by commands of the app with the same name:
+    /// - `help [COMMAND]...` writes the help of the app or of a command.
+    /// - `history` writes the lines entered so far, `!N` runs the line `N` again.
+    /// - `exit` and `quit` end the loop.
+    ///
+    /// A line ending with a tab writes its completions, see [`App::complete()`].
+    ///
+    /// # Errors:
+    /// Returns [`AppError::IoError`] if reading `input` or writing `output` fails.
+    ///
+    /// # Example:
+    /// ```
+    /// use ecp::builder::*;
+    /// use std::io::Write;
+    ///
+    /// let app = App::new("Shop").command(Command::new("buy").flag(Flag::new("gift")));
+    ///
+    /// let input = "buy apples --gift\nexit\n".as_bytes();
+    /// let mut output = Vec::new();
+    ///
+    /// app.repl(input, &mut output, |cli, output| {
+    ///     writeln!(output, "bought {:?}", cli.get_values().collect::<Vec<_>>())?;
+    ///     Ok(())
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(output).unwrap(), "Shop> bought [\"apples\"]\nShop> ");
+    /// ```
+    pub fn repl<R, W, F>(
+        &self,
+        mut input: R,
+        mut output: W,
+        mut callback: F,
+    ) -> Result<(), AppError>
+    where
+        R: BufRead,
+        W: Write,
+        F: FnMut(CommandParsed, &mut W) -> Result<(), AppError>,
+    {
+        let mut history: Vec<String> = Vec::new();
+        let mut buffer = String::new();
+
+        loop {
+            write!(output, "{}> ", self.name)?;
+            output.flush()?;
+
+            buffer.clear();
+            if input.read_line(&mut buffer)? == 0 {
+                return Ok(());
+            }
+
+            let raw = buffer.trim_end_matches(['\n', '\r']);
+
+            if let Some(partial) = raw.strip_suffix('\t') {
+                writeln!(output, "{}", self.complete(partial).join("  "))?;
+                continue;
+            }
+
+            let mut line = raw.trim().to_string();
+
+            if let Some(number) = line.strip_prefix('!') {
+                let entry = number
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|n| history.get(n.wrapping_sub(1)));
+
+                match entry {
+                    Some(entry) => {
+                        line = entry.clone();
+                        writeln!(output, "{}", line)?;
+                    }
+                    None => {
+                        let error = AppError::InvalidInput(format!("No history entry: {}", number));
+                        writeln!(output, "{}", error)?;
+                        continue;
+                    }
+                }
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            history.push(line.clone());
+
+            let args = match split_line(&line) {
+                Ok(args) => args,
+                Err(e) => {
+                    writeln!(output, "{}", e)?;
+                    continue;
+                }
+            };
+
+            let builtin = args.first().filter(|name| {
+                !self
+                    .commands
+                    .iter()
+                    .any(|cmd| cmd.get_names().any(|n| n == *name))
+            });
+
+            match builtin.map(String::as_str) {
+                Some("exit" | "quit") => return Ok(()),
+                Some("history") => {
+                    for (i, entry) in history.iter().enumerate() {
+                        writeln!(output, "{:>5}  {}", i + 1, entry)?;
+                    }
+                }
+                Some("help") => {
+                    let path: Vec<&str> = args[1..].iter().map(String::as_str).collect();
+
+                    let help = if path.is_empty() {
+                        Ok(self.render_help())
+                    } else {
+                        self.render_command_help(&path)
+                    };
+
+                    match help {
+                        Ok(help) => write!(output, "{}", help)?,
+                        Err(e) => writeln!(output, "{}", e)?,
+                    }
+                }
+                _ => {
+                    let result = self.try_parse_line(&line).and_then(|parsed| {
+                        for warning in parsed.get_warnings() {
+                            writeln!(output, "Warning: {}", warning)?;
+                        }
+
+                        callback(parsed, &mut output)
+                    });
+
+                    if let Err(e) = result {
+                        writeln!(output, "{}", e)?;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use std::env;
+
+/// A terminal color, written as an ANSI escape code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    /// One of the 256 colors of most terminals.
+    Ansi256(u8),
+    /// A true color, for terminals that support it.
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Returns the ANSI parameters that set the color as foreground.
+    fn code(self) -> String {
+        match self {
+            Color::Black => "30".to_string(),
+            Color::Red => "31".to_string(),
+            Color::Green => "32".to_string(),
+            Color::Yellow => "33".to_string(),
+            Color::Blue => "34".to_string(),
+            Color::Magenta => "35".to_string(),
+            Color::Cyan => "36".to_string(),
+            Color::White => "37".to_string(),
+            Color::Ansi256(color) => format!("38;5;{}", color),
+            Color::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+}
+
+/// The style of a piece of output, like a bold red `Error:`.
+///
+/// # Example:
+/// ```
+/// use ecp::style::{Color, Style};
+///
+/// let style = Style::new().bold().fg(Color::Red);
+///
+/// assert_eq!(style.paint("Error:"), "\x1b[1;31mError:\x1b[0m");
+/// assert_eq!(Style::new().paint("Error:"), "Error:");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub(crate) bold: bool,
+    pub(crate) dimmed: bool,
+    pub(crate) italic: bool,
+    pub(crate) underline: bool,
+    pub(crate) fg: Option<Color>,
+}
+
+impl Style {
+    /// Creates a [`Style`] that leaves text unchanged.
+    pub const fn new() -> Style {
+        Style {
+            bold: false,
+            dimmed: false,
+            italic: false,
+            underline: false,
+            fg: None,
+        }
+    }
+
+    pub const fn bold(mut self) -> Style {
+        self.bold = true;
+        self
+    }
+
+    pub const fn dimmed(mut self) -> Style {
+        self.dimmed = true;
+        self
+    }
+
+    pub const fn italic(mut self) -> Style {
+        self.italic = true;
+        self
+    }
+
+    pub const fn underline(mut self) -> Style {
+        self.underline = true;
+        self
+    }
+
+    /// Sets the foreground color.
+    pub const fn fg(mut self, color: Color) -> Style {
+        self.fg = Some(color);
+        self
+    }
+
+    /// Returns `text` wrapped in the escape codes of the style, or unchanged if the style is
+    /// empty.
+    pub fn paint(&self, text: &str) -> String {
+        let mut codes = Vec::new();
+
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if self.dimmed {
+            codes.push("2".to_string());
+        }
+        if self.italic {
+            codes.push("3".to_string());
+        }
+        if self.underline {
+            codes.push("4".to_string());
+        }
+        if let Some(color) = self.fg {
+            codes.push(color.code());
+        }
+
+        if codes.is_empty() || text.is_empty() {
+            return text.to_string();
+        }
+
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+/// The styles of help and error output, set with [`App::styles()`](crate::builder::App::styles).
+///
+/// # Example:
+/// ```
+/// use ecp::builder::*;
+/// use ecp::style::{Color, Style, Styles};
+///
+/// let brand = Styles::default()
+///     .header(Style::new().bold().fg(Color::Rgb(255, 94, 0)))
+///     .literal(Style::new().fg(Color::Ansi256(208)));
+///
+/// let app = App::new("Rust").styles(brand);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Styles {
+    pub(crate) header: Style,
+    pub(crate) literal: Style,
+    pub(crate) placeholder: Style,
+    pub(crate) error: Style,
+    pub(crate) warning: Style,
+    pub(crate) suggestion: Style,
+}
+
+impl Styles {
+    /// Creates [`Styles`] that leave every piece of output unchanged.
+    pub const fn plain() -> Styles {
+        Styles {
+            header: Style::new(),
+            literal: Style::new(),
+            placeholder: Style::new(),
+            error: Style::new(),
+            warning: Style::new(),
+            suggestion: Style::new(),
+        }
+    }
+
+    /// Sets the style of section headers, like `Usage:` and `Flags:`.
+    pub const fn header(mut self, style: Style) -> Styles {
+        self.header = style;
+        self
+    }
+
+    /// Sets the style of text typed as is, like command and flag names.
+    pub const fn literal(mut self, style: Style) -> Styles {
+        self.literal = style;
+        self
+    }
+
+    /// Sets the style of value placeholders, like `<VALUE>`.
+    pub const fn placeholder(mut self, style: Style) -> Styles {
+        self.placeholder = style;
+        self
+    }
+
+    /// Sets the style of the `Error:` prefix.
+    pub const fn error(mut self, style: Style) -> Styles {
+        self.error = style;
+        self
+    }
+
+    /// Sets the style of the `Warning:` prefix.
+    pub const fn warning(mut self, style: Style) -> Styles {
+        self.warning = style;
+        self
+    }
+
+    /// Sets the style of the names suggested for a typo.
+    pub const fn suggestion(mut self, style: Style) -> Styles {
+        self.suggestion = style;
+        self
+    }
+
+    pub fn get_header(&self) -> Style {
+        self.header
+    }
+
+    pub fn get_literal(&self) -> Style {
+        self.literal
+    }
+
+    pub fn get_placeholder(&self) -> Style {
+        self.placeholder
+    }
+
+    pub fn get_error(&self) -> Style {
+        self.error
+    }
+
+    pub fn get_warning(&self) -> Style {
+        self.warning
+    }
+
+    pub fn get_suggestion(&self) -> Style {
+        self.suggestion
+    }
+}
+
+/// Bold and underlined headers, bold literals, italic placeholders, a bold red `Error:`, a bold
+/// yellow `Warning:` and green suggestions.
+impl Default for Styles {
+    fn default() -> Styles {
+        Styles {
+            header: Style::new().bold().underline(),
+            literal: Style::new().bold(),
+            placeholder: Style::new().italic(),
+            error: Style::new().bold().fg(Color::Red),
+            warning: Style::new().bold().fg(Color::Yellow),
+            suggestion: Style::new().fg(Color::Green),
+        }
+    }
+}
+
+/// When output is styled, set with [`App::color()`](crate::builder::App::color) or the built-in
+/// `--color=<auto|always|never>` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Styles output written to a terminal, unless `NO_COLOR` is set. `CLICOLOR_FORCE` styles
+    /// output that isn't written to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Returns `true` if output written to a terminal, or not, should be styled.
+    pub fn enables_color(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let var = |name| env::var_os(name).filter(|value| !value.is_empty());
+
+                if var("NO_COLOR").is_some() {
+                    false
+                } else if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    true
+                } else {
+                    is_terminal
+                }
+            }
+        }
+    }
+}
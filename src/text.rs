@@ -0,0 +1,129 @@
+// Display width and word wrapping for help output, without pulling in unicode-width
+
+/// Returns the number of terminal columns `c` takes: 0 for control and combining characters,
+/// 2 for wide East Asian characters and emoji, and 1 for everything else.
+///
+/// The ranges follow the wide and zero width blocks of Unicode 15 but are an approximation, a
+/// few narrow characters inside the emoji blocks are counted as wide and emoji sequences joined
+/// with U+200D are counted per character.
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x00..=0x1F | 0x7F..=0x9F => 0,
+        0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x0E31
+        | 0x0E34..=0x0E3A
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => 0,
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x23E9..=0x23EC
+        | 0x23F0
+        | 0x23F3
+        | 0x25FD..=0x25FE
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x267F
+        | 0x2693
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26CE
+        | 0x26D4
+        | 0x26EA
+        | 0x26F2..=0x26F3
+        | 0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x274E
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2B55
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F004
+        | 0x1F0CF
+        | 0x1F18E
+        | 0x1F191..=0x1F19A
+        | 0x1F200..=0x1F265
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F7E0..=0x1F7EB
+        | 0x1F7F0
+        | 0x1F90C..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// Returns the number of terminal columns `s` takes, see [`char_width()`].
+pub(crate) fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+/// Splits `text` into lines of at most `width` columns, breaking at whitespace. Lines that fit
+/// are kept as they are, so aligned text stays aligned, and words wider than `width` get a line
+/// of their own.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let paragraph = paragraph.trim_end();
+
+        if display_width(paragraph) <= width {
+            lines.push(paragraph.to_string());
+            continue;
+        }
+
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in paragraph.split_whitespace() {
+            let word_width = display_width(word);
+
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+
+            line.push_str(word);
+            line_width += word_width;
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
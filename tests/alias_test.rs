@@ -0,0 +1,83 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn cargo() -> App {
+    App::new("Rust").command(
+        Command::new("cargo")
+            .subcommand(
+                Command::new("build")
+                    .visible_alias("b")
+                    .flag(Flag::new("release").short('r').alias("optimized"))
+                    .flag(Flag::new("locked").short_alias('L')),
+            )
+            .subcommand(
+                Command::new("bench")
+                    .flag(Flag::new("release").short('r'))
+                    .flag(Flag::new("locked")),
+            )
+            .subcommand(
+                Command::new("run")
+                    .alias("execute")
+                    .flag(Flag::new("release").short('r'))
+                    .flag(Flag::new("registry"))
+                    .flag(Flag::new("locked")),
+            ),
+    )
+}
+
+#[test]
+fn aliases() {
+    let cli = cargo()
+        .try_parse_from(["ecp", "cargo", "b", "--optimized", "-L"])
+        .unwrap();
+
+    assert_eq!(cli.get_subcommand(), Some("build"));
+    assert!(cli.get_flags().any(|f| f == "release"));
+    assert!(cli.get_flags().any(|f| f == "locked"));
+
+    let app = cargo();
+    let run = &app.get_commands().next().unwrap().get_subcommands()[2];
+    assert_eq!(run.get_visible_aliases().count(), 0);
+    assert_eq!(run.get_aliases().collect::<Vec<_>>(), vec!["execute"]);
+}
+
+#[test]
+fn prefixes() {
+    let cli = cargo()
+        .allow_prefixes(true)
+        .try_parse_from(["ecp", "car", "ru", "--rel", "--lo"])
+        .unwrap();
+
+    assert_eq!(cli.get_command(), "cargo");
+    assert_eq!(cli.get_subcommand(), Some("run"));
+    assert!(cli.get_flags().any(|f| f == "release"));
+    assert!(cli.get_flags().any(|f| f == "locked"));
+
+    // Prefixes are only accepted when enabled
+    assert!(matches!(
+        cargo().try_parse_from(["ecp", "car", "run", "--release"]),
+        Err(AppError::InvalidCommand(_))
+    ));
+}
+
+#[test]
+fn ambiguous_prefixes() {
+    match cargo()
+        .allow_prefixes(true)
+        .try_parse_from(["ecp", "cargo", "be", "-r"])
+    {
+        Err(AppError::AmbiguousCommand(_, _)) => panic!("`be` only matches `bench`"),
+        result => assert_eq!(result.unwrap().get_subcommand(), Some("bench")),
+    }
+
+    match cargo()
+        .allow_prefixes(true)
+        .try_parse_from(["ecp", "cargo", "ru", "--re"])
+    {
+        Err(AppError::AmbiguousFlag(flag, candidates)) => {
+            assert_eq!(flag, "re");
+            assert_eq!(candidates, vec!["release", "registry"]);
+        }
+        _ => panic!("expected an ambiguous flag"),
+    }
+}
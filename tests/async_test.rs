@@ -0,0 +1,145 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use ecp::builder::{App, Command};
+use ecp::dispatch::{BoxFuture, Context};
+use ecp::errors::{AppError, ErrorFormat};
+
+/// Wakes the thread blocked in [`block_on()`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// A minimal executor, parks the thread until the future is woken.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = std::task::Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// A future that is pending on its first poll, like a socket waiting for a reply.
+async fn reply(message: &str) -> String {
+    let mut pending = true;
+
+    std::future::poll_fn(|cx| {
+        if pending {
+            pending = false;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+
+    format!("daemon: {}", message)
+}
+
+struct Replies(Vec<String>);
+
+fn app() -> App {
+    App::new("Ctl")
+        .before(|_, ctx| {
+            ctx.insert(Replies(Vec::new()));
+            Ok(())
+        })
+        .command(Command::new("ping").async_handler(|cli, ctx| {
+            Box::pin(async move {
+                for value in cli.get_values() {
+                    let reply = reply(value).await;
+                    ctx.get_mut::<Replies>().unwrap().0.push(reply);
+                }
+                Ok(())
+            })
+        }))
+        .command(
+            Command::new("stop")
+                .async_handler(|_, _| {
+                    Box::pin(async { Err(AppError::InvalidInput("daemon is down".to_string())) })
+                })
+                .on_error(|_, ctx, error| {
+                    ctx.get_mut::<Replies>().unwrap().0.push(error.to_string());
+                    Ok(())
+                }),
+        )
+        .command(Command::new("version").handler(|_, ctx| {
+            ctx.get_mut::<Replies>()
+                .unwrap()
+                .0
+                .push("0.1.0".to_string());
+            Ok(())
+        }))
+}
+
+fn replies(ctx: &mut Context) -> Vec<String> {
+    ctx.remove::<Replies>().unwrap().0
+}
+
+#[test]
+fn awaits_async_handlers() {
+    let app = app();
+    let mut ctx = Context::new();
+
+    block_on(app.try_dispatch_async_from(["ctl", "ping", "a", "b"], &mut ctx)).unwrap();
+    assert_eq!(replies(&mut ctx), ["daemon: a", "daemon: b"]);
+
+    block_on(app.try_dispatch_async_from(["ctl", "stop"], &mut ctx)).unwrap();
+    assert_eq!(replies(&mut ctx), ["Error: Invalid input: daemon is down"]);
+}
+
+#[test]
+fn returns_diagnostics() {
+    let app = app();
+    let mut ctx = Context::new();
+
+    block_on(app.try_dispatch_async_diagnostics_from(["ctl", "ping", "a"], &mut ctx)).unwrap();
+    assert_eq!(replies(&mut ctx), ["daemon: a"]);
+
+    let diagnostics = block_on(
+        app.try_dispatch_async_diagnostics_from(["ctl", "--error-format=json", "pong"], &mut ctx),
+    )
+    .unwrap_err();
+    assert_eq!(diagnostics[0].get_token(), Some("pong"));
+    assert_eq!(diagnostics[0].get_error_format(), ErrorFormat::Json);
+}
+
+#[test]
+fn calls_sync_handlers() {
+    let app = app();
+    let mut ctx = Context::new();
+
+    block_on(app.try_dispatch_async_from(["ctl", "version"], &mut ctx)).unwrap();
+    assert_eq!(replies(&mut ctx), ["0.1.0"]);
+
+    app.try_dispatch_from(["ctl", "version"], &mut ctx).unwrap();
+    assert_eq!(replies(&mut ctx), ["0.1.0"]);
+}
+
+#[test]
+fn sync_dispatch_rejects_async_handlers() {
+    let result = app().try_dispatch_from(["ctl", "ping"], &mut Context::new());
+
+    assert!(matches!(result, Err(AppError::AsyncHandler(path)) if path == "ping"));
+}
+
+#[test]
+fn handler_futures_are_send() {
+    fn assert_send<T: Send>() {}
+
+    assert_send::<BoxFuture<'static>>();
+    assert_send::<Context>();
+}
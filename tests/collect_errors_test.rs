@@ -0,0 +1,108 @@
+use std::process::ExitCode;
+
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::{AppError, ErrorKind};
+
+fn app(collect: bool) -> App {
+    App::new("Rust").collect_errors(collect).command(
+        Command::new("cargo")
+            .flag(Flag::new("jobs").short('j').takes_value(true))
+            .flag(Flag::new("offline"))
+            .flag(Flag::new("config").key_value(true))
+            .flag(Flag::new("target").min_occurrences(1))
+            .subcommand(Command::new("build")),
+    )
+}
+
+fn errors(result: Result<ecp::parser::CommandParsed, AppError>) -> Vec<String> {
+    match result {
+        Err(AppError::Multiple(errors)) => errors.iter().map(|e| e.to_string()).collect(),
+        Err(e) => panic!("expected multiple errors, found {}", e),
+        Ok(_) => panic!("expected multiple errors"),
+    }
+}
+
+#[test]
+fn stops_at_the_first_error_by_default() {
+    let result = app(false).try_parse_from(["rust", "cargo", "--nope", "--jobs"]);
+
+    assert!(matches!(result, Err(AppError::InvalidFlag(_))));
+}
+
+#[test]
+fn collects_recoverable_errors() {
+    let result = app(true).try_parse_from([
+        "rust",
+        "cargo",
+        "--nope",
+        "--offline=yes",
+        "--config",
+        "key",
+        "-x",
+        "--jobs",
+    ]);
+
+    assert_eq!(
+        errors(result),
+        [
+            "Error: Invalid flag: Flag not found: --nope for `cargo`",
+            "Error: Unexpected value: `--offline` doesn't take a value",
+            "Error: Invalid flag: Flag not found: -x for `cargo`",
+            "Error: Missing value: `--jobs` requires a value",
+            "Error: Invalid number of occurrences: `--target` must occur at least 1 time(s), found 0",
+            "Error: Invalid value: `--config` expects `key=value`, found `key`",
+        ]
+    );
+}
+
+#[test]
+fn stops_at_commands_that_are_not_found() {
+    let result = app(true).try_parse_from(["rust", "cargo", "--nope", "biuld", "--nope"]);
+
+    assert_eq!(
+        errors(result),
+        [
+            "Error: Invalid flag: Flag not found: --nope for `cargo`",
+            "Error: Invalid command: Subcommand not found: biuld",
+        ]
+    );
+}
+
+#[test]
+fn valid_args_still_parse() {
+    let cli = app(true)
+        .try_parse_from(["rust", "cargo", "--target", "build"])
+        .unwrap();
+
+    assert_eq!(cli.get_subcommand(), Some("build"));
+}
+
+#[test]
+fn help_is_not_collected() {
+    let result = app(true).try_parse_from(["rust", "cargo", "--nope", "--help"]);
+
+    assert!(matches!(result, Err(AppError::DisplayHelp(_))));
+}
+
+#[test]
+fn display_and_exit_code() {
+    let error = AppError::Multiple(vec![
+        AppError::InvalidValue("x".to_string()),
+        AppError::InvalidFlag("--y".to_string()),
+    ]);
+
+    assert_eq!(
+        error.to_string(),
+        "Error: 2 errors:\n  - Invalid value: x\n  - Invalid flag: --y"
+    );
+    assert_eq!(error.kind(), ErrorKind::Multiple);
+    assert_eq!(error.exit_code(), 65);
+
+    let app = app(true).exit_code(ErrorKind::InvalidValue, 3);
+    let result = app.try_parse_from(["rust", "cargo", "--target", "--config", "key"]);
+    assert_eq!(app.report(result), ExitCode::from(3));
+
+    let app = app.exit_code(ErrorKind::Multiple, 4);
+    let result = app.try_parse_from(["rust", "cargo", "--target", "--config", "key"]);
+    assert_eq!(app.report(result), ExitCode::from(4));
+}
@@ -0,0 +1,147 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+use ecp::style::{Color, ColorChoice, Style, Styles};
+
+fn app(color: ColorChoice) -> App {
+    App::new("Rust").color(color).command(
+        Command::new("cargo").subcommand(
+            Command::new("build")
+                .description("Compile the current package")
+                .flag(
+                    Flag::new("release")
+                        .short('r')
+                        .description("Build in release mode"),
+                )
+                .flag(Flag::new("jobs").takes_value(true).description("Jobs")),
+        ),
+    )
+}
+
+/// Returns the help printed for `args` when stdout is styled.
+fn help(app: &App, args: &[&str]) -> String {
+    let diagnostics = app.try_parse_diagnostics_from(args).err().unwrap();
+
+    match diagnostics[0].get_error() {
+        AppError::DisplayHelp(help) => diagnostics[0].get_styled_help().unwrap_or(help).to_string(),
+        _ => panic!("expected help for {:?}", args),
+    }
+}
+
+fn strip_escapes(s: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| *c == 'm');
+        } else {
+            plain.push(c);
+        }
+    }
+
+    plain
+}
+
+#[test]
+fn paint() {
+    assert_eq!(
+        Style::new().bold().underline().paint("Usage:"),
+        "\x1b[1;4mUsage:\x1b[0m"
+    );
+    assert_eq!(
+        Style::new().fg(Color::Ansi256(208)).paint("x"),
+        "\x1b[38;5;208mx\x1b[0m"
+    );
+    assert_eq!(
+        Style::new().italic().fg(Color::Rgb(1, 2, 3)).paint("x"),
+        "\x1b[3;38;2;1;2;3mx\x1b[0m"
+    );
+    assert_eq!(Style::new().bold().paint(""), "");
+}
+
+#[test]
+fn styled_help() {
+    let app = app(ColorChoice::Always);
+    let help = help(&app, &["rust", "cargo", "build", "--help"]);
+
+    assert!(
+        help.contains(
+            "\x1b[1;4mUsage:\x1b[0m \x1b[1mRust cargo build\x1b[0m \x1b[3m[FLAGS]\x1b[0m"
+        )
+    );
+    assert!(help.contains("  \x1b[1m-r\x1b[0m, \x1b[1m--release\x1b[0m"));
+    assert!(help.contains("\x1b[1m--jobs\x1b[0m \x1b[3m<VALUE>\x1b[0m  Jobs"));
+
+    // Escape codes don't break the alignment of the columns
+    assert_eq!(
+        strip_escapes(&help),
+        app.render_command_help(&["cargo", "build"]).unwrap()
+    );
+
+    // The error holds the plain help, like the one written by the REPL
+    match app.try_parse_from(["rust", "cargo", "build", "-h"]) {
+        Err(AppError::DisplayHelp(help)) => assert!(!help.contains('\x1b')),
+        _ => panic!("expected help"),
+    }
+}
+
+#[test]
+fn plain_help() {
+    let app = app(ColorChoice::Never);
+
+    assert_eq!(help(&app, &["rust", "--help"]), app.render_help());
+    assert!(!app.render_help().contains('\x1b'));
+}
+
+#[test]
+fn custom_styles() {
+    let styles = Styles::plain().header(Style::new().fg(Color::Magenta));
+    let app = app(ColorChoice::Always).styles(styles);
+
+    assert_eq!(
+        app.get_styles().get_header(),
+        Style::new().fg(Color::Magenta)
+    );
+    assert!(help(&app, &["rust", "-h"]).contains("\x1b[35mUsage:\x1b[0m Rust <COMMAND>"));
+}
+
+#[test]
+fn color_flag() {
+    let app = app(ColorChoice::Auto);
+
+    assert!(help(&app, &["rust", "--color=always", "-h"]).contains('\x1b'));
+    assert!(!help(&app, &["rust", "cargo", "--color=never", "-h"]).contains('\x1b'));
+
+    // The flag only applies to the parse it's given in
+    let cli = app
+        .try_parse_from(["rust", "cargo", "--color=never", "build"])
+        .unwrap();
+    assert_eq!(cli.get_color(), ColorChoice::Never);
+    assert_eq!(app.get_color(), ColorChoice::Auto);
+
+    let cli = app.try_parse_from(["rust", "cargo", "build"]).unwrap();
+    assert_eq!(cli.get_color(), ColorChoice::Auto);
+
+    assert!(matches!(
+        app.try_parse_from(["rust", "--color=sometimes", "cargo"]),
+        Err(AppError::InvalidValue(_))
+    ));
+
+    let app = App::new("Ls").command(Command::new("ls").flag(Flag::new("color").takes_value(true)));
+    let cli = app.try_parse_from(["ls", "ls", "--color=always"]).unwrap();
+
+    assert_eq!(cli.get_flag_value("color"), Some("always"));
+    assert_eq!(cli.get_color(), ColorChoice::Auto);
+}
+
+#[test]
+fn color_choice() {
+    assert!(ColorChoice::Always.enables_color(false));
+    assert!(!ColorChoice::Never.enables_color(true));
+
+    let diagnostics = app(ColorChoice::Never)
+        .try_parse_diagnostics_from(["rust", "-h"])
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics[0].get_styled_help(), None);
+}
@@ -0,0 +1,153 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::dispatch::Context;
+use ecp::errors::{AppError, ErrorFormat};
+
+/// The path of the handler that ran and the values it received.
+#[derive(Debug, PartialEq)]
+struct Called(&'static str, Vec<String>);
+
+fn handler(
+    name: &'static str,
+) -> impl Fn(&ecp::parser::CommandParsed, &mut Context) -> Result<(), AppError> {
+    move |cli, ctx| {
+        let values = cli.get_values().map(|v| v.to_string()).collect();
+        ctx.insert(Called(name, values));
+        Ok(())
+    }
+}
+
+fn app() -> App {
+    App::new("Rust")
+        .command(
+            Command::new("cargo")
+                .handler(handler("cargo"))
+                .subcommand(
+                    Command::new("build")
+                        .alias("b")
+                        .flag(Flag::new("release"))
+                        .handler(handler("cargo build")),
+                )
+                .subcommand(Command::new("run")),
+        )
+        .command(
+            Command::new("rustup").subcommand(Command::new("update").handler(|cli, _| {
+                match cli.get_values().next() {
+                    Some(toolchain) => Err(AppError::InvalidValue(toolchain.to_string())),
+                    None => Ok(()),
+                }
+            })),
+        )
+}
+
+fn dispatch(app: &App, args: &[&str]) -> (Result<(), AppError>, Option<Called>) {
+    let mut ctx = Context::new();
+    let result = app.try_dispatch_from(args, &mut ctx);
+
+    (result, ctx.remove::<Called>())
+}
+
+#[test]
+fn routes_to_the_deepest_handler() {
+    let app = app();
+
+    let (result, called) = dispatch(&app, &["rust", "cargo", "b", "--release", "ecp"]);
+    assert!(result.is_ok());
+    assert_eq!(called, Some(Called("cargo build", vec!["ecp".to_string()])));
+
+    let (result, called) = dispatch(&app, &["rust", "cargo"]);
+    assert!(result.is_ok());
+    assert_eq!(called, Some(Called("cargo", Vec::new())));
+}
+
+#[test]
+fn falls_back_to_the_parent() {
+    let (result, called) = dispatch(&app(), &["rust", "cargo", "run", "x"]);
+
+    assert!(result.is_ok());
+    assert_eq!(called, Some(Called("cargo", vec!["x".to_string()])));
+}
+
+#[test]
+fn falls_back_to_help() {
+    let app = app();
+    let (result, called) = dispatch(&app, &["rust", "rustup"]);
+
+    match result {
+        Err(AppError::DisplayHelp(help)) => {
+            assert_eq!(help, app.render_command_help(&["rustup"]).unwrap())
+        }
+        _ => panic!("expected help"),
+    }
+    assert_eq!(called, None);
+}
+
+#[test]
+fn returns_errors() {
+    let app = app();
+
+    let (result, _) = dispatch(&app, &["rust", "rustup", "update", "nightly"]);
+    assert!(matches!(result, Err(AppError::InvalidValue(v)) if v == "nightly"));
+
+    let (result, _) = dispatch(&app, &["rust", "cargo", "--nope"]);
+    assert!(matches!(result, Err(AppError::InvalidFlag(_))));
+}
+
+#[test]
+fn returns_diagnostics() {
+    let app = app();
+    let mut ctx = Context::new();
+
+    let diagnostics = app
+        .try_dispatch_diagnostics_from(
+            ["rust", "--error-format=json", "cargo", "--relase"],
+            &mut ctx,
+        )
+        .unwrap_err();
+    assert_eq!(diagnostics[0].get_token(), Some("--relase"));
+    assert_eq!(diagnostics[0].get_error_format(), ErrorFormat::Json);
+
+    // Errors of the handler keep the format picked for the parse
+    let diagnostics = app
+        .try_dispatch_diagnostics_from(
+            ["rust", "rustup", "--error-format=json", "update", "nightly"],
+            &mut ctx,
+        )
+        .unwrap_err();
+    assert!(matches!(diagnostics[0].get_error(), AppError::InvalidValue(v) if v == "nightly"));
+    assert_eq!(diagnostics[0].get_token(), None);
+    assert_eq!(diagnostics[0].get_error_format(), ErrorFormat::Json);
+
+    let diagnostics = app
+        .try_dispatch_diagnostics_from(["rust", "rustup"], &mut ctx)
+        .unwrap_err();
+    assert!(matches!(
+        diagnostics[0].get_error(),
+        AppError::DisplayHelp(_)
+    ));
+
+    assert!(
+        app.try_dispatch_diagnostics_from(["rust", "cargo"], &mut ctx)
+            .is_ok()
+    );
+}
+
+#[test]
+fn context() {
+    let mut ctx = Context::new();
+
+    assert_eq!(ctx.insert(1_u32), None);
+    assert_eq!(ctx.insert(2_u32), Some(1));
+    *ctx.get_mut::<u32>().unwrap() += 1;
+
+    assert!(ctx.contains::<u32>());
+    assert_eq!(ctx.remove::<u32>(), Some(3));
+    assert!(!ctx.contains::<u32>());
+}
+
+#[test]
+fn handlers_are_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<App>();
+    assert_send_sync::<Command>();
+}
@@ -0,0 +1,156 @@
+use std::process::{ExitCode, Termination};
+
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::{AppError, ErrorKind};
+
+fn app() -> App {
+    App::new("Rust")
+        .version("1.0.0")
+        .command(
+            Command::new("cargo")
+                .description("Rust's package manager")
+                .subcommand(Command::new("build").flag(Flag::new("release").short('r'))),
+        )
+        .command(Command::new("ls").flag(Flag::new("human").short('h')))
+}
+
+#[test]
+fn default_exit_codes() {
+    let cases = [
+        (AppError::InvalidFlag("--x".to_string()), 64),
+        (AppError::MissingValue("env".to_string()), 64),
+        (AppError::AmbiguousCommand("b".to_string(), Vec::new()), 64),
+        (AppError::InvalidValue("x".to_string()), 65),
+        (AppError::InvalidDefinition(Vec::new()), 70),
+        (AppError::IoError(std::io::ErrorKind::Other.into()), 74),
+        (AppError::DisplayHelp(String::new()), 0),
+        (AppError::DisplayVersion(String::new()), 0),
+    ];
+
+    for (error, code) in cases {
+        assert_eq!(error.exit_code(), code, "exit code of {:?}", error);
+    }
+}
+
+#[test]
+fn configured_exit_codes() {
+    let app = app()
+        .exit_code(ErrorKind::InvalidFlag, 1)
+        .exit_code(ErrorKind::InvalidFlag, 2)
+        .exit_code(ErrorKind::DisplayHelp, 3);
+
+    assert_eq!(app.get_exit_code(ErrorKind::InvalidFlag), 2);
+    assert_eq!(app.get_exit_code(ErrorKind::InvalidCommand), 64);
+
+    let result = app.try_parse_from(["rust", "cargo", "--nope"]);
+    assert_eq!(app.report(result), ExitCode::from(2));
+
+    let result = app.try_parse_from(["rust", "--help"]);
+    assert_eq!(app.report(result), ExitCode::from(3));
+
+    let result = app.try_parse_from(["rust", "cargo"]);
+    assert_eq!(app.report(result), ExitCode::SUCCESS);
+}
+
+#[test]
+fn termination() {
+    let app = app();
+
+    assert_eq!(
+        app.try_parse_from(["rust", "cargo"]).unwrap().report(),
+        ExitCode::SUCCESS
+    );
+    assert_eq!(
+        AppError::InvalidValue("x".to_string()).report(),
+        ExitCode::from(65)
+    );
+}
+
+#[test]
+fn builtin_help() {
+    let app = app();
+
+    match app.try_parse_from(["rust", "-h"]) {
+        Err(AppError::DisplayHelp(help)) => assert_eq!(help, app.render_help()),
+        _ => panic!("expected the app help"),
+    }
+
+    for args in [
+        &["rust", "cargo", "build", "--help"][..],
+        &["rust", "cargo", "build", "-r", "-h", "--nope"],
+    ] {
+        match app.try_parse_from(args) {
+            Err(AppError::DisplayHelp(help)) => {
+                assert_eq!(help, app.render_command_help(&["cargo", "build"]).unwrap())
+            }
+            _ => panic!("expected the help of `cargo build` for {:?}", args),
+        }
+    }
+}
+
+#[test]
+fn builtin_help_is_shadowed_by_flags() {
+    let cli = app().try_parse_from(["rust", "ls", "-h"]).unwrap();
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), ["human"]);
+
+    assert!(matches!(
+        app().try_parse_from(["rust", "ls", "--help"]),
+        Err(AppError::DisplayHelp(_))
+    ));
+}
+
+#[test]
+fn builtin_version() {
+    match app().try_parse_from(["rust", "--version"]) {
+        Err(e @ AppError::DisplayVersion(_)) => assert_eq!(e.to_string(), "Rust 1.0.0"),
+        _ => panic!("expected the version"),
+    }
+
+    match app().try_parse_from(["rust", "cargo", "build", "--version"]) {
+        Err(e @ AppError::DisplayVersion(_)) => assert_eq!(e.to_string(), "Rust 1.0.0"),
+        _ => panic!("expected the version"),
+    }
+    assert!(matches!(
+        App::new("Rust")
+            .command(Command::new("cargo"))
+            .try_parse_from(["rust", "--version"]),
+        Err(AppError::InvalidCommand(_))
+    ));
+}
+
+#[test]
+fn builtins_are_not_taken_by_prefixes() {
+    let app = App::new("Rust")
+        .version("1.0.0")
+        .allow_prefixes(true)
+        .command(
+            Command::new("cargo")
+                .flag(Flag::new("help-all"))
+                .flag(Flag::new("version-file").takes_value(true))
+                .flag(Flag::new("colorize"))
+                .flag(Flag::new("error-formats")),
+        );
+
+    assert!(matches!(
+        app.try_parse_from(["rust", "cargo", "--help"]),
+        Err(AppError::DisplayHelp(_))
+    ));
+    assert!(matches!(
+        app.try_parse_from(["rust", "cargo", "--version"]),
+        Err(AppError::DisplayVersion(_))
+    ));
+
+    let cli = app
+        .try_parse_from(["rust", "cargo", "--color=never", "--error-format=json"])
+        .unwrap();
+    assert_eq!(cli.get_flags().count(), 0);
+
+    // Prefixes that aren't a built-in still match
+    let cli = app
+        .try_parse_from(["rust", "cargo", "--help-", "--colo", "--error"])
+        .unwrap();
+    assert_eq!(
+        cli.get_flags().collect::<Vec<_>>(),
+        ["help-all", "colorize", "error-formats"]
+    );
+}
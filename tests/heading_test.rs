@@ -0,0 +1,114 @@
+use ecp::builder::{App, Command, Flag};
+
+fn app() -> App {
+    App::new("Git")
+        .term_width(80)
+        .command(Command::new("commit").description("Record changes"))
+        .command(
+            Command::new("cat-file")
+                .description("Show object contents")
+                .help_heading("Plumbing commands"),
+        )
+        .command(
+            Command::new("rev-parse")
+                .description("Pick out parameters")
+                .help_heading("Plumbing commands")
+                .display_order(1),
+        )
+        .command(
+            Command::new("add")
+                .description("Add file contents")
+                .display_order(0),
+        )
+        .command(
+            Command::new("log")
+                .description("Show commit logs")
+                .flag(Flag::new("verbose").short('v'))
+                .flag(Flag::new("oneline").help_heading("Output options"))
+                .flag(Flag::new("proxy").takes_value(true).help_heading("Network"))
+                .flag(
+                    Flag::new("graph")
+                        .help_heading("Output options")
+                        .display_order(0),
+                )
+                .flag(Flag::new("debug").hidden(true).help_heading("Debugging"))
+                .flag(Flag::new("quiet").short('q').display_order(5)),
+        )
+}
+
+#[test]
+fn grouped_commands() {
+    assert_eq!(
+        app().render_help(),
+        concat!(
+            "Git\n",
+            "\n",
+            "Usage: Git <COMMAND>\n",
+            "\n",
+            "Commands:\n",
+            "  add     Add file contents\n",
+            "  commit  Record changes\n",
+            "  log     Show commit logs\n",
+            "\n",
+            "Plumbing commands:\n",
+            "  rev-parse  Pick out parameters\n",
+            "  cat-file   Show object contents\n",
+        )
+    );
+}
+
+#[test]
+fn grouped_flags() {
+    assert_eq!(
+        app().render_command_help(&["log"]).unwrap(),
+        concat!(
+            "Show commit logs\n",
+            "\n",
+            "Usage: Git log [FLAGS]\n",
+            "\n",
+            "Flags:\n",
+            "  -q, --quiet\n",
+            "  -v, --verbose\n",
+            "\n",
+            "Output options:\n",
+            "      --graph\n",
+            "      --oneline\n",
+            "\n",
+            "Network:\n",
+            "      --proxy <VALUE>\n",
+        )
+    );
+}
+
+#[test]
+fn headings_follow_display_order() {
+    let app = App::new("Tool").command(
+        Command::new("run")
+            .flag(Flag::new("a").help_heading("Later"))
+            .flag(Flag::new("b").help_heading("Sooner").display_order(0)),
+    );
+
+    let help = app.render_command_help(&["run"]).unwrap();
+    assert!(help.find("Sooner:").unwrap() < help.find("Later:").unwrap());
+}
+
+#[test]
+fn grouped_docs() {
+    let app = app();
+
+    let markdown = app.render_markdown();
+    assert!(markdown.contains(concat!(
+        "| `-v, --verbose` |  |\n\n",
+        "#### Output options\n\n",
+        "| Flag | Description |\n",
+        "| --- | --- |\n",
+        "| `--graph` |  |\n",
+        "| `--oneline` |  |\n\n",
+        "#### Network\n\n",
+    )));
+    assert!(markdown.find("### `Git add`").unwrap() < markdown.find("### `Git commit`").unwrap());
+
+    let man = app.render_man();
+    assert!(man.contains(".PP\n.B \"Output options\"\n.TP\n.B \\-\\-graph\n"));
+    assert!(!man.contains("Debugging"));
+}
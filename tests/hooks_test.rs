@@ -0,0 +1,134 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::dispatch::Context;
+use ecp::errors::AppError;
+use ecp::parser::CommandParsed;
+
+/// The steps run by the dispatch, in order.
+#[derive(Default)]
+struct Log(Vec<String>);
+
+fn step(name: &'static str) -> impl Fn(&CommandParsed, &mut Context) -> Result<(), AppError> {
+    move |cli, ctx| {
+        ctx.get_mut::<Log>().unwrap().0.push(name.to_string());
+
+        if cli.get_values().any(|v| v == name) {
+            Err(AppError::InvalidValue(name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn on_error(
+    name: &'static str,
+    handles: bool,
+) -> impl Fn(&CommandParsed, &mut Context, AppError) -> Result<(), AppError> {
+    move |_, ctx, error| {
+        ctx.get_mut::<Log>()
+            .unwrap()
+            .0
+            .push(format!("{} {}", name, error));
+
+        if handles { Ok(()) } else { Err(error) }
+    }
+}
+
+fn app() -> App {
+    App::new("Rust")
+        .before(step("app before"))
+        .after(step("app after"))
+        .on_error(on_error("app on_error", false))
+        .command(
+            Command::new("cargo")
+                .before(step("cargo before"))
+                .after(step("cargo after"))
+                .on_error(on_error("cargo on_error", false))
+                .subcommand(
+                    Command::new("build")
+                        .flag(Flag::new("release"))
+                        .before(step("build before 1"))
+                        .before(step("build before 2"))
+                        .after(step("build after"))
+                        .handler(step("build")),
+                ),
+        )
+        .command(
+            Command::new("rustup")
+                .on_error(on_error("rustup on_error", true))
+                .handler(step("rustup")),
+        )
+}
+
+fn dispatch(app: &App, args: &[&str]) -> (Result<(), AppError>, Vec<String>) {
+    let mut ctx = Context::new();
+    ctx.insert(Log::default());
+
+    let result = app.try_dispatch_from(args, &mut ctx);
+
+    (result, ctx.remove::<Log>().unwrap().0)
+}
+
+#[test]
+fn order() {
+    let (result, log) = dispatch(&app(), &["rust", "cargo", "build"]);
+
+    assert!(result.is_ok());
+    assert_eq!(
+        log,
+        [
+            "app before",
+            "cargo before",
+            "build before 1",
+            "build before 2",
+            "build",
+            "build after",
+            "cargo after",
+            "app after",
+        ]
+    );
+}
+
+#[test]
+fn errors_stop_the_dispatch() {
+    let (result, log) = dispatch(&app(), &["rust", "cargo", "build", "cargo before"]);
+
+    assert!(matches!(result, Err(AppError::InvalidValue(v)) if v == "cargo before"));
+    assert_eq!(
+        log,
+        [
+            "app before",
+            "cargo before",
+            "cargo on_error Error: Invalid value: cargo before",
+            "app on_error Error: Invalid value: cargo before",
+        ]
+    );
+
+    let (result, log) = dispatch(&app(), &["rust", "cargo", "build", "build"]);
+
+    assert!(result.is_err());
+    assert_eq!(log[4], "build");
+    assert_eq!(log[5], "cargo on_error Error: Invalid value: build");
+}
+
+#[test]
+fn on_error_handles_the_error() {
+    let (result, log) = dispatch(&app(), &["rust", "rustup", "rustup"]);
+
+    assert!(result.is_ok());
+    assert_eq!(
+        log,
+        [
+            "app before",
+            "rustup",
+            "rustup on_error Error: Invalid value: rustup",
+        ]
+    );
+}
+
+#[test]
+fn parse_errors_skip_hooks() {
+    let (result, log) = dispatch(&app(), &["rust", "cargo", "build", "--nope"]);
+
+    assert!(matches!(result, Err(AppError::InvalidFlag(_))));
+    assert!(log.is_empty());
+}
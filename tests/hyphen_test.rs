@@ -0,0 +1,86 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn seek() -> App {
+    App::new("Seek").command(
+        Command::new("seek")
+            .subcommand(
+                Command::new("file")
+                    .flag(Flag::new("offset").short('o').takes_value(true))
+                    .flag(
+                        Flag::new("args")
+                            .takes_value(true)
+                            .allow_hyphen_values(true),
+                    )
+                    .flag(Flag::new("verbose").short('v')),
+            )
+            .subcommand(
+                Command::new("line")
+                    .flag(Flag::new("first").short('1'))
+                    .flag(Flag::new("count").short('c').takes_value(true)),
+            )
+            .subcommand(
+                Command::new("exec")
+                    .allow_hyphen_values(true)
+                    .flag(Flag::new("verbose").short('v')),
+            ),
+    )
+}
+
+#[test]
+fn negative_numbers() {
+    let cli = seek()
+        .try_parse_from([
+            "seek", "seek", "file", "--offset", "-5", "-3.2", "-", "-o", "-.5", "-v",
+        ])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_flag_values("offset").collect::<Vec<_>>(),
+        vec!["-5", "-.5"]
+    );
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["-3.2", "-"]);
+    assert!(cli.get_flags().any(|f| f == "verbose"));
+}
+
+#[test]
+fn numbers_matching_a_short_flag() {
+    // `-1` is a flag in `line`, so is any number starting with `1`
+    let cli = seek()
+        .try_parse_from(["seek", "seek", "line", "-1", "-2", "-c", "-20"])
+        .unwrap();
+
+    assert!(cli.get_flags().any(|f| f == "first"));
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["-2"]);
+    assert_eq!(cli.get_flag_value("count"), Some("-20"));
+    assert!(matches!(
+        seek().try_parse_from(["seek", "seek", "line", "-c", "-10"]),
+        Err(AppError::MissingValue(flag)) if flag == "count"
+    ));
+}
+
+#[test]
+fn hyphen_values() {
+    let cli = seek()
+        .try_parse_from(["seek", "seek", "file", "--args", "-x", "--args", "--v"])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_flag_values("args").collect::<Vec<_>>(),
+        vec!["-x", "--v"]
+    );
+    assert!(matches!(
+        seek().try_parse_from(["seek", "seek", "file", "--offset", "-x"]),
+        Err(AppError::MissingValue(flag)) if flag == "offset"
+    ));
+
+    let cli = seek()
+        .try_parse_from(["seek", "seek", "exec", "-x", "--all", "-v", "-inf"])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_values().collect::<Vec<_>>(),
+        vec!["-x", "--all", "-inf"]
+    );
+    assert!(cli.get_flags().any(|f| f == "verbose"));
+}
@@ -0,0 +1,165 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::{AppError, Diagnostic, ErrorFormat};
+
+fn app() -> App {
+    App::new("Mvn").command(
+        Command::new("package")
+            .flag(Flag::new("define").short('D').key_value(true))
+            .flag(Flag::new("quiet").short('q').negatable(true))
+            .flag(Flag::new("threads").takes_value(true))
+            .subcommand(Command::new("deploy"))
+            .subcommand(Command::new("install")),
+    )
+}
+
+#[test]
+fn command_parsed() {
+    let cli = app()
+        .try_parse_from(["mvn", "package", "-D", "env=prod", "--no-quiet"])
+        .unwrap();
+
+    assert_eq!(
+        cli.to_json(),
+        concat!(
+            r#"{"path":["package"],"flags":["define"],"#,
+            r#""states":{"define":true,"quiet":false,"threads":false},"#,
+            r#""occurrences":[{"flag":"define","index":2,"state":true,"values":["env=prod"]},"#,
+            r#"{"flag":"quiet","index":4,"state":false,"values":[]}],"#,
+            r#""properties":{"define":{"env":["prod"]}},"values":[],"unknown":[],"warnings":[]}"#
+        )
+    );
+}
+
+#[test]
+fn escapes_strings() {
+    let cli = App::new("Say")
+        .command(Command::new("say"))
+        .try_parse_from(["say", "say", "a \"quote\"\\\n\u{1}é"])
+        .unwrap();
+
+    assert!(
+        cli.to_json()
+            .contains(r#""values":["a \"quote\"\\\n\u0001é"]"#)
+    );
+}
+
+#[test]
+fn diagnostics() {
+    let diagnostics = app()
+        .try_parse_diagnostics_from(["mvn", "package", "--qiuet"])
+        .err()
+        .unwrap();
+
+    assert_eq!(
+        Diagnostic::list_to_json(&diagnostics),
+        concat!(
+            r#"{"errors":[{"kind":"invalid_flag","#,
+            r#""message":"Invalid flag: Flag not found: --qiuet for `package`","#,
+            r#""token":"--qiuet","position":2,"suggestions":["--quiet"]}]}"#
+        )
+    );
+
+    let diagnostics = app()
+        .try_parse_diagnostics_from(["mvn", "package", "instal"])
+        .err()
+        .unwrap();
+
+    assert_eq!(diagnostics[0].get_token(), Some("instal"));
+    assert_eq!(diagnostics[0].get_suggestions(), ["install"]);
+
+    let diagnostics = app()
+        .try_parse_diagnostics_from(["mvn", "package", "-x", "--threads"])
+        .err()
+        .unwrap();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].get_suggestions().is_empty());
+}
+
+#[test]
+fn collected_diagnostics() {
+    let app = app().collect_errors(true);
+    let diagnostics = app
+        .try_parse_diagnostics_from(["mvn", "package", "--qiuet", "-D", "x", "--threads"])
+        .err()
+        .unwrap();
+
+    let located: Vec<(Option<&str>, Option<usize>)> = diagnostics
+        .iter()
+        .map(|d| (d.get_token(), d.get_position()))
+        .collect();
+
+    assert_eq!(
+        located,
+        [
+            (Some("--qiuet"), Some(2)),
+            (Some("--threads"), Some(5)),
+            (None, None)
+        ]
+    );
+    assert!(matches!(
+        diagnostics[2].get_error(),
+        AppError::InvalidValue(_)
+    ));
+}
+
+#[test]
+fn app_error() {
+    let error = AppError::Multiple(vec![
+        AppError::AmbiguousFlag(
+            "re".to_string(),
+            vec!["release".to_string(), "remote".to_string()],
+        ),
+        AppError::UnexpectedValue("quiet".to_string()),
+    ]);
+
+    assert_eq!(
+        error.to_json(),
+        concat!(
+            r#"{"errors":[{"kind":"ambiguous_flag","#,
+            r#""message":"Ambiguous flag: `--re` could be `--release`, `--remote`","#,
+            r#""token":null,"position":null,"suggestions":["--release","--remote"]},"#,
+            r#"{"kind":"unexpected_value","#,
+            r#""message":"Unexpected value: `--quiet` doesn't take a value","#,
+            r#""token":null,"position":null,"suggestions":[]}]}"#
+        )
+    );
+}
+
+#[test]
+fn error_format_flag() {
+    // Errors kept before the flag are printed in its format too
+    let diagnostics = app()
+        .collect_errors(true)
+        .try_parse_diagnostics_from(["mvn", "package", "--nope", "--error-format=json"])
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics[0].get_token(), Some("--nope"));
+    assert_eq!(diagnostics[0].get_error_format(), ErrorFormat::Json);
+
+    let app = app();
+
+    let cli = app
+        .try_parse_from(["mvn", "package", "--error-format=json"])
+        .unwrap();
+    assert_eq!(cli.get_error_format(), ErrorFormat::Json);
+    assert_eq!(app.get_error_format(), ErrorFormat::Text);
+
+    let cli = app.try_parse_from(["mvn", "package"]).unwrap();
+    assert_eq!(cli.get_error_format(), ErrorFormat::Text);
+
+    assert!(matches!(
+        app.try_parse_from(["mvn", "--error-format=xml", "package"]),
+        Err(AppError::InvalidValue(_))
+    ));
+
+    let app = App::new("Mvn")
+        .error_format(ErrorFormat::Json)
+        .command(Command::new("package").flag(Flag::new("error-format").takes_value(true)));
+
+    let cli = app
+        .try_parse_from(["mvn", "package", "--error-format=text"])
+        .unwrap();
+    assert_eq!(cli.get_flag_value("error-format"), Some("text"));
+    assert_eq!(cli.get_error_format(), ErrorFormat::Json);
+}
@@ -0,0 +1,115 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn app() -> App {
+    App::new("Rust").term_width(40).command(
+        Command::new("build")
+            .description("Compile the package")
+            .long_description(
+                "Compile the current package and all of its dependencies.\n\nArtifacts go in the target directory.",
+            )
+            .flag(
+                Flag::new("release")
+                    .short('r')
+                    .description("Build in release mode")
+                    .long_description("Build in release mode, with optimizations"),
+            )
+            .flag(Flag::new("locked").description("Require Cargo.lock")),
+    )
+}
+
+fn help(args: &[&str]) -> String {
+    match app().try_parse_from(args) {
+        Err(AppError::DisplayHelp(help)) => help,
+        _ => panic!("expected help for {:?}", args),
+    }
+}
+
+#[test]
+fn short_help() {
+    assert_eq!(
+        help(&["rust", "build", "-h"]),
+        concat!(
+            "Compile the package\n",
+            "\n",
+            "Usage: Rust build [FLAGS]\n",
+            "\n",
+            "Flags:\n",
+            "  -r, --release  Build in release mode\n",
+            "      --locked   Require Cargo.lock\n",
+        )
+    );
+    assert_eq!(
+        help(&["rust", "build", "-h"]),
+        app().render_command_help(&["build"]).unwrap()
+    );
+}
+
+#[test]
+fn long_help() {
+    assert_eq!(
+        help(&["rust", "build", "--help"]),
+        concat!(
+            "Compile the current package and all of\n",
+            "its dependencies.\n",
+            "\n",
+            "Artifacts go in the target directory.\n",
+            "\n",
+            "Usage: Rust build [FLAGS]\n",
+            "\n",
+            "Flags:\n",
+            "  -r, --release  Build in release mode,\n",
+            "                 with optimizations\n",
+            "      --locked   Require Cargo.lock\n",
+        )
+    );
+    assert_eq!(
+        help(&["rust", "build", "--help"]),
+        app().render_long_command_help(&["build"]).unwrap()
+    );
+}
+
+#[test]
+fn long_help_falls_back_to_the_description() {
+    let app = App::new("Rust").command(Command::new("fmt").description("Format the code"));
+
+    assert_eq!(
+        app.render_long_command_help(&["fmt"]).unwrap(),
+        app.render_command_help(&["fmt"]).unwrap()
+    );
+    assert_eq!(
+        app.get_commands().next().unwrap().get_long_description(),
+        None
+    );
+}
+
+#[test]
+fn shadowed_short_help() {
+    let app = App::new("Rust").command(
+        Command::new("ls")
+            .description("List files")
+            .long_description("List files, one per line")
+            .flag(Flag::new("human").short('h')),
+    );
+
+    let cli = app.try_parse_from(["rust", "ls", "-h"]).unwrap();
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), ["human"]);
+
+    match app.try_parse_from(["rust", "ls", "--help"]) {
+        Err(AppError::DisplayHelp(help)) => assert!(help.starts_with("List files, one per line\n")),
+        _ => panic!("expected help"),
+    }
+}
+
+#[test]
+fn docs_use_long_descriptions() {
+    let app = app();
+
+    let man = app.render_man();
+    assert!(man.contains("Artifacts go in the target directory.\n"));
+    assert!(man.contains("Build in release mode, with optimizations\n"));
+
+    let markdown = app.render_markdown();
+    assert!(markdown.contains("Compile the current package and all of its dependencies.\n\n"));
+    assert!(markdown.contains("| `-r, --release` | Build in release mode, with optimizations |\n"));
+}
@@ -0,0 +1,73 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn ls() -> App {
+    App::new("Coreutils").command(
+        Command::new("ls").subcommand(
+            Command::new("list")
+                .flag(
+                    Flag::new("color")
+                        .alias("colour")
+                        .negatable(true)
+                        .enabled_by_default(true),
+                )
+                .flag(Flag::new("all").short('a').negatable(true))
+                .flag(Flag::new("long").short('l')),
+        ),
+    )
+}
+
+#[test]
+fn negated_flags() {
+    let cli = ls()
+        .try_parse_from(["ecp", "ls", "list", "--no-colour", "-l"])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_state("color"), Some(false));
+    assert_eq!(cli.get_flag_state("all"), Some(false));
+    assert_eq!(cli.get_flag_state("long"), Some(true));
+    assert_eq!(cli.get_flag_state("size"), None);
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), vec!["long"]);
+}
+
+#[test]
+fn last_one_wins() {
+    let cli = ls()
+        .try_parse_from([
+            "ecp",
+            "ls",
+            "list",
+            "-a",
+            "--no-color",
+            "--no-all",
+            "--color",
+        ])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_state("color"), Some(true));
+    assert_eq!(cli.get_flag_state("all"), Some(false));
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), vec!["color"]);
+}
+
+#[test]
+fn default_state() {
+    let cli = ls().try_parse_from(["ecp", "ls", "list", "-a"]).unwrap();
+
+    assert_eq!(cli.get_flag_state("color"), Some(true));
+    assert_eq!(cli.get_flag_state("all"), Some(true));
+    assert_eq!(cli.get_flag_state("long"), Some(false));
+}
+
+#[test]
+fn negated_flags_take_no_value() {
+    let app = App::new("Coreutils")
+        .command(Command::new("ls").flag(Flag::new("color").takes_value(true).negatable(true)));
+
+    match app.try_parse_from(["ecp", "ls", "--no-color=always"]) {
+        Err(e @ AppError::UnexpectedValue(_)) => assert_eq!(
+            e.to_string(),
+            "Error: Unexpected value: `--no-color` doesn't take a value"
+        ),
+        _ => panic!("expected an unexpected value"),
+    }
+}
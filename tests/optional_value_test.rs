@@ -0,0 +1,63 @@
+use ecp::builder::{App, Command, Flag};
+
+fn app() -> App {
+    App::new("App").command(
+        Command::new("app").subcommand(
+            Command::new("run")
+                .flag(
+                    Flag::new("color")
+                        .short('c')
+                        .default_missing_value("always"),
+                )
+                .flag(Flag::new("log").short('l').optional_value(true))
+                .flag(Flag::new("verbose").short('v')),
+        ),
+    )
+}
+
+#[test]
+fn value_attached_with_equals() {
+    let cli = app()
+        .try_parse_from(["app", "app", "run", "--color=never", "-l=debug"])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_value("color"), Some("never"));
+    assert_eq!(cli.get_flag_value("log"), Some("debug"));
+    assert_eq!(cli.get_values().count(), 0);
+}
+
+#[test]
+fn value_attached_to_short_flag() {
+    let cli = app()
+        .try_parse_from(["app", "app", "run", "-vl=debug", "-cv"])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_value("log"), Some("debug"));
+    assert_eq!(cli.get_flag_value("color"), Some("always"));
+    assert_eq!(cli.get_flag_count("verbose"), 2);
+}
+
+#[test]
+fn bare_flags_never_take_the_next_arg() {
+    let cli = app()
+        .try_parse_from(["app", "app", "run", "--color", "auto", "-l", "main.rs"])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_value("color"), Some("always"));
+    assert_eq!(cli.get_flag_value("log"), None);
+    assert_eq!(cli.get_flag_count("log"), 1);
+    assert_eq!(
+        cli.get_values().collect::<Vec<_>>(),
+        vec!["auto", "main.rs"]
+    );
+}
+
+#[test]
+fn bare_occurrence_keeps_earlier_value() {
+    let cli = app()
+        .try_parse_from(["app", "app", "run", "--log=info", "--log", "-v"])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_value("log"), Some("info"));
+    assert_eq!(cli.get_flag_values("log").collect::<Vec<_>>(), vec!["info"]);
+}
@@ -0,0 +1,224 @@
+use ecp::builder::{App, Command, DuplicateKeys, Flag};
+use proptest::prelude::*;
+use std::ffi;
+
+// Names are taken from a small pool so generated args often match the generated app
+const NAMES: &[&str] = &["a", "b", "ab", "run", "build", "no-a"];
+
+// Names rejected by `App::validate()`
+const INVALID_NAMES: &[&str] = &["", "-x", "a=b", "a b"];
+
+fn name() -> impl Strategy<Value = String> {
+    prop::sample::select(NAMES).prop_map(str::to_string)
+}
+
+fn invalid_name() -> impl Strategy<Value = String> {
+    prop::sample::select([NAMES, INVALID_NAMES].concat()).prop_map(str::to_string)
+}
+
+fn flag() -> impl Strategy<Value = Flag> {
+    (
+        name(),
+        prop::collection::vec(prop::sample::select(vec!['a', 'b', '1']), 0..3),
+        prop::collection::vec(name(), 0..2),
+        any::<[bool; 7]>(),
+        prop::option::of(prop::sample::select(vec![',', '=', '-'])),
+        0..3usize,
+        prop::option::of(0..3usize),
+    )
+        .prop_map(|(long, shorts, aliases, settings, delimiter, min, max)| {
+            let [
+                negatable,
+                takes_value,
+                key_value,
+                optional,
+                hyphen,
+                collect,
+                missing,
+            ] = settings;
+            let mut flag = Flag::new(&long)
+                .negatable(negatable)
+                .enabled_by_default(negatable && takes_value)
+                .takes_value(takes_value)
+                .key_value(key_value)
+                .optional_value(optional)
+                .allow_hyphen_values(hyphen)
+                .min_occurrences(min);
+
+            // The first short is the short name, the others are short aliases
+            let mut shorts = shorts.into_iter();
+            if let Some(short) = shorts.next() {
+                flag = flag.short(short);
+            }
+            for alias in shorts {
+                flag = flag.short_alias(alias);
+            }
+            for alias in aliases {
+                flag = flag.alias(&alias);
+            }
+            if let Some(delimiter) = delimiter {
+                flag = flag.value_delimiter(delimiter);
+            }
+            if let Some(max) = max {
+                flag = flag.max_occurrences(max);
+            }
+            if collect {
+                flag = flag.duplicate_keys(DuplicateKeys::Collect);
+            }
+            if missing {
+                flag = flag.default_missing_value("");
+            }
+
+            flag
+        })
+}
+
+fn command() -> impl Strategy<Value = Command> {
+    let leaf = (
+        name(),
+        prop::collection::vec(flag(), 0..4),
+        prop::collection::vec(name(), 0..2),
+        any::<bool>(),
+    )
+        .prop_map(|(name, flags, aliases, hyphen)| {
+            let mut cmd = Command::new(&name).allow_hyphen_values(hyphen);
+            for alias in aliases.iter().filter(|alias| **alias != name) {
+                cmd = cmd.alias(alias);
+            }
+            add_flags(cmd, flags)
+        });
+
+    leaf.prop_recursive(3, 16, 3, |inner| {
+        (
+            name(),
+            prop::collection::vec(flag(), 0..3),
+            prop::collection::vec(inner, 0..3),
+        )
+            .prop_map(|(name, flags, subcommands)| {
+                let mut cmd = add_flags(Command::new(&name), flags);
+                for subcommand in subcommands {
+                    let next = cmd.clone().subcommand(subcommand);
+                    if is_valid(std::slice::from_ref(&next)) {
+                        cmd = next;
+                    }
+                }
+                cmd
+            })
+    })
+}
+
+/// Adds the flags that keep `cmd` valid, dropping the ones that collide with a flag added before.
+fn add_flags(mut cmd: Command, flags: Vec<Flag>) -> Command {
+    for flag in flags {
+        let next = cmd.clone().flag(flag);
+        if is_valid(std::slice::from_ref(&next)) {
+            cmd = next;
+        }
+    }
+    cmd
+}
+
+/// Returns `true` if an app with `commands` passes [`App::validate()`].
+fn is_valid(commands: &[Command]) -> bool {
+    commands
+        .iter()
+        .cloned()
+        .fold(App::new("app"), App::command)
+        .validate()
+        .is_ok()
+}
+
+/// Builds a valid app from generated commands, dropping the ones that collide with a command
+/// added before. Since `App` isn't `Debug` it can't be generated itself.
+fn build_app(commands: Vec<Command>, prefixes: bool, unknown: bool) -> App {
+    let mut kept: Vec<Command> = Vec::new();
+    for command in commands {
+        kept.push(command);
+        if !is_valid(&kept) {
+            kept.pop();
+        }
+    }
+
+    kept.into_iter().fold(
+        App::new("app")
+            .allow_prefixes(prefixes)
+            .allow_unknown(unknown),
+        App::command,
+    )
+}
+
+fn arg() -> impl Strategy<Value = String> {
+    prop_oneof![
+        name(),
+        name().prop_map(|name| format!("--{}", name)),
+        name().prop_map(|name| format!("--no-{}", name)),
+        name().prop_map(|name| format!("-{}", name)),
+        (name(), name()).prop_map(|(name, value)| format!("--{}={}", name, value)),
+        prop::sample::select(vec![
+            "-", "--", "-5", "-1.5", "-1", "-=", "-a=", "k=v", "é", "-é", "", "-x", "a=b",
+            "--a=b=c", "-ab", "-ba1",
+        ])
+        .prop_map(str::to_string),
+        any::<String>(),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn parsing_never_panics(
+        commands in prop::collection::vec(command(), 0..4),
+        prefixes in any::<bool>(),
+        unknown in any::<bool>(),
+        args in prop::collection::vec(arg(), 0..8),
+    ) {
+        let app = build_app(commands, prefixes, unknown);
+        let args: Vec<ffi::OsString> = args.into_iter().map(ffi::OsString::from).collect();
+
+        // Every generated app is valid, so the args reach the parser in debug builds too
+        prop_assert!(app.validate().is_ok());
+
+        // Parsing must return, either `Ok` or `Err`
+        let _ = app.try_parse_args(args);
+    }
+
+    #[test]
+    fn validation_never_panics(
+        commands in prop::collection::vec(command(), 0..4),
+        invalid in prop::collection::vec(
+            (invalid_name(), invalid_name(), prop::sample::select(vec!['a', '-', '=', ' '])),
+            0..4,
+        ),
+    ) {
+        let mut app = build_app(commands, false, false);
+        for (name, long, short) in invalid {
+            app = app.command(Command::new(&name).flag(Flag::new(&long).short(short).short_alias(short)));
+        }
+
+        let _ = app.validate();
+    }
+
+    // Release builds skip `App::validate()`, so invalid apps reach the parser there
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn parsing_invalid_apps_never_panics(
+        commands in prop::collection::vec(command(), 0..4),
+        invalid in prop::collection::vec(
+            (invalid_name(), invalid_name(), prop::sample::select(vec!['a', '-', '=', ' '])),
+            0..4,
+        ),
+        flags in prop::collection::vec(flag(), 0..4),
+        prefixes in any::<bool>(),
+        unknown in any::<bool>(),
+        args in prop::collection::vec(arg(), 0..8),
+    ) {
+        let mut app = build_app(commands, prefixes, unknown);
+        for (name, long, short) in invalid {
+            app = app.command(Command::new(&name).flag(Flag::new(&long).short(short).short_alias(short)));
+        }
+        // Flags that may collide with each other
+        app = app.command(flags.into_iter().fold(Command::new("a"), Command::flag));
+        let args: Vec<ffi::OsString> = args.into_iter().map(ffi::OsString::from).collect();
+
+        let _ = app.try_parse_args(args);
+    }
+}
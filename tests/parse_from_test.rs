@@ -0,0 +1,65 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+use std::ffi;
+
+fn cargo() -> App {
+    App::new("Rust").command(
+        Command::new("cargo").subcommand(
+            Command::new("run")
+                .flag(Flag::new("release").short('r'))
+                .flag(Flag::new("bin").takes_value(true)),
+        ),
+    )
+}
+
+#[test]
+fn any_args() {
+    let from_str = cargo()
+        .try_parse_from(["ecp", "cargo", "run", "-r"])
+        .unwrap();
+    let from_string = cargo()
+        .try_parse_from(vec![
+            String::from("ecp"),
+            String::from("cargo"),
+            String::from("run"),
+            String::from("-r"),
+        ])
+        .unwrap();
+    let from_os_string = cargo()
+        .try_parse_from(
+            ["ecp", "cargo", "run", "-r"]
+                .iter()
+                .map(ffi::OsString::from),
+        )
+        .unwrap();
+
+    for cli in [from_str, from_string, from_os_string] {
+        assert_eq!(cli.get_command(), "cargo");
+        assert_eq!(cli.get_subcommand(), Some("run"));
+        assert!(cli.get_flags().any(|f| f == "release"));
+    }
+}
+
+#[test]
+fn no_binary_name() {
+    let cli = cargo()
+        .no_binary_name(true)
+        .try_parse_from(["cargo", "run", "--bin", "ecp"])
+        .unwrap();
+
+    assert_eq!(cli.get_command(), "cargo");
+    assert_eq!(cli.get_flag_value("bin"), Some("ecp"));
+    assert_eq!(cli.get_flag_indices("bin").collect::<Vec<_>>(), vec![2]);
+
+    // Without it the first arg is the binary name
+    assert!(matches!(
+        cargo().try_parse_from(["cargo", "run"]),
+        Err(AppError::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        cargo()
+            .no_binary_name(true)
+            .try_parse_from(Vec::<String>::new()),
+        Err(AppError::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,71 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+use ecp::parser::split_line;
+
+#[test]
+fn shell_quoting() {
+    let cases: &[(&str, &[&str])] = &[
+        ("  deploy   web\t-v ", &["deploy", "web", "-v"]),
+        (
+            r#"say "a \"quoted\" \$word" 'it''s'"#,
+            &["say", r#"a "quoted" $word"#, "its"],
+        ),
+        (
+            r#"say 'no \escapes "here"'"#,
+            &["say", r#"no \escapes "here""#],
+        ),
+        (r#"say "keeps \n \a""#, &["say", r"keeps \n \a"]),
+        (r"say a\ b \'c\'", &["say", "a b", "'c'"]),
+        ("say \"\" ''", &["say", "", ""]),
+        ("say --env=\"staging eu\"x", &["say", "--env=staging eux"]),
+        (
+            "say one\\\ntwo \"three\\\nfour\"",
+            &["say", "onetwo", "threefour"],
+        ),
+        ("", &[]),
+    ];
+
+    for (line, args) in cases {
+        assert_eq!(split_line(line).unwrap(), *args, "splitting {:?}", line);
+    }
+}
+
+#[test]
+fn unterminated_quotes() {
+    for (line, expected) in [
+        ("deploy \"web", "Unterminated \" quote starting at column 8"),
+        ("deploy 'wé", "Unterminated ' quote starting at column 8"),
+        ("deploy web\\", "Trailing backslash at column 11"),
+    ] {
+        match split_line(line) {
+            Err(AppError::InvalidInput(msg)) => assert_eq!(msg, expected),
+            _ => panic!("expected an error for {:?}", line),
+        }
+    }
+}
+
+#[test]
+fn parse_line() {
+    let app = App::new("Bot").command(
+        Command::new("deploy").subcommand(
+            Command::new("web")
+                .flag(Flag::new("env").takes_value(true))
+                .flag(Flag::new("force").short('f')),
+        ),
+    );
+
+    let cli = app
+        .try_parse_line(r#"deploy web --env "staging eu" -f 'my app'"#)
+        .unwrap();
+
+    assert_eq!(cli.get_command(), "deploy");
+    assert_eq!(cli.get_subcommand(), Some("web"));
+    assert_eq!(cli.get_flag_value("env"), Some("staging eu"));
+    assert_eq!(cli.get_flag_indices("env").collect::<Vec<_>>(), vec![3]);
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["my app"]);
+
+    assert!(matches!(
+        app.try_parse_line("deploy web --env \"staging"),
+        Err(AppError::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,80 @@
+use ecp::builder::{App, Command, DuplicateKeys, Flag};
+use ecp::errors::AppError;
+
+fn mvn(policy: DuplicateKeys) -> App {
+    App::new("Maven").command(
+        Command::new("mvn").subcommand(
+            Command::new("package")
+                .flag(
+                    Flag::new("define")
+                        .short('D')
+                        .key_value(true)
+                        .duplicate_keys(policy),
+                )
+                .flag(Flag::new("set").key_value(true))
+                .flag(Flag::new("offline").short('o')),
+        ),
+    )
+}
+
+#[test]
+fn property_map() {
+    let cli = mvn(DuplicateKeys::LastWins)
+        .try_parse_from([
+            "mvn",
+            "mvn",
+            "package",
+            "-D",
+            "env=dev",
+            "-Dskip.tests=true",
+            "-D=profile=ci",
+            "--define=url=http://a?b=c",
+            "-D",
+            "env=prod",
+            "-o",
+        ])
+        .unwrap();
+
+    let define = cli.get_properties("define").unwrap();
+    assert_eq!(
+        define.keys().collect::<Vec<_>>(),
+        vec!["env", "skip.tests", "profile", "url"]
+    );
+    assert_eq!(define.get("profile"), Some("ci"));
+    assert_eq!(define.get("env"), Some("prod"));
+    assert_eq!(define.get_all("env").count(), 1);
+    assert_eq!(define.get("url"), Some("http://a?b=c"));
+    assert!(cli.get_properties("set").unwrap().is_empty());
+    assert!(cli.get_properties("offline").is_none());
+}
+
+#[test]
+fn duplicate_keys() {
+    let input = ["mvn", "mvn", "package", "-D", "env=dev", "-D", "env=prod"];
+
+    let cli = mvn(DuplicateKeys::Collect).try_parse_from(input).unwrap();
+    let define = cli.get_properties("define").unwrap();
+    assert_eq!(
+        define.get_all("env").collect::<Vec<_>>(),
+        vec!["dev", "prod"]
+    );
+    assert_eq!(
+        define.iter().collect::<Vec<_>>(),
+        vec![("env", "dev"), ("env", "prod")]
+    );
+
+    assert!(matches!(
+        mvn(DuplicateKeys::Error).try_parse_from(input),
+        Err(AppError::DuplicateKey(_))
+    ));
+}
+
+#[test]
+fn invalid_keys() {
+    for value in ["env", "=dev", "my env=dev"] {
+        assert!(matches!(
+            mvn(DuplicateKeys::LastWins).try_parse_from(["mvn", "mvn", "package", "-D", value]),
+            Err(AppError::InvalidValue(_))
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+use std::io::Write;
+
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+use ecp::style::ColorChoice;
+
+fn app() -> App {
+    App::new("Admin")
+        .command(
+            Command::new("user")
+                .description("Manage users")
+                .subcommand(
+                    Command::new("add")
+                        .description("Add a user")
+                        .flag(
+                            Flag::new("admin")
+                                .short('a')
+                                .description("Grant admin rights"),
+                        )
+                        .flag(Flag::new("color").negatable(true)),
+                )
+                .subcommand(Command::new("remove")),
+        )
+        .command(Command::new("restart"))
+}
+
+/// Runs the REPL over `input`, writing the command path and values of every parsed line.
+fn run(app: &App, input: &str) -> String {
+    let mut output = Vec::new();
+
+    app.repl(input.as_bytes(), &mut output, |cli, output| {
+        let path: Vec<&str> = cli.get_command_path().collect();
+        let values: Vec<&str> = cli.get_values().collect();
+        writeln!(output, "{} {:?}", path.join(" "), values)?;
+        Ok(())
+    })
+    .unwrap();
+
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn parses_lines_until_exit() {
+    let output = run(&app(), "user add 'Ana Lima'\n\nrestart\nexit\nrestart\n");
+
+    assert_eq!(
+        output,
+        "Admin> user add [\"Ana Lima\"]\nAdmin> Admin> restart []\nAdmin> "
+    );
+}
+
+#[test]
+fn ends_on_eof() {
+    assert_eq!(run(&app(), "restart"), "Admin> restart []\nAdmin> ");
+}
+
+#[test]
+fn errors_do_not_end_the_loop() {
+    let output = run(&app(), "deploy\nuser add \"x\nrestart\n");
+
+    assert_eq!(
+        output,
+        "Admin> Error: Invalid command: Command not found: deploy\n\
+         Admin> Error: Invalid input: Unterminated \" quote starting at column 10\n\
+         Admin> restart []\nAdmin> "
+    );
+
+    let mut output = Vec::new();
+    app()
+        .repl("restart\n".as_bytes(), &mut output, |_, _| {
+            Err(AppError::InvalidValue("not now".to_string()))
+        })
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Admin> Error: Invalid value: not now\nAdmin> "
+    );
+}
+
+#[test]
+fn help() {
+    let app = app();
+    let output = run(&app, "help\nhelp user add\nhelp nope\n");
+
+    assert_eq!(
+        output,
+        format!(
+            "Admin> {}Admin> {}Admin> Error: Invalid command: Command not found: nope\nAdmin> ",
+            app.render_help(),
+            app.render_command_help(&["user", "add"]).unwrap()
+        )
+    );
+
+    assert_eq!(
+        app.render_command_help(&["user", "add"]).unwrap(),
+        "Add a user\n\n\
+         Usage: Admin user add [FLAGS]\n\n\
+         Flags:\n  \
+         -a, --admin       Grant admin rights\n      \
+         --[no-]color\n"
+    );
+}
+
+#[test]
+fn help_flag_is_plain() {
+    let app = app().color(ColorChoice::Always);
+    let output = run(&app, "user add -h\n");
+
+    assert_eq!(
+        output,
+        format!(
+            "Admin> {}\nAdmin> ",
+            app.render_command_help(&["user", "add"])
+                .unwrap()
+                .trim_end()
+        )
+    );
+}
+
+#[test]
+fn history() {
+    let output = run(&app(), "restart\nuser remove x\nhistory\n!2\n!9\n");
+
+    assert_eq!(
+        output,
+        "Admin> restart []\n\
+         Admin> user remove [\"x\"]\n\
+         Admin>     1  restart\n    2  user remove x\n    3  history\n\
+         Admin> user remove x\nuser remove [\"x\"]\n\
+         Admin> Error: Invalid input: No history entry: 9\n\
+         Admin> "
+    );
+}
+
+#[test]
+fn builtins_are_shadowed_by_commands() {
+    let app = App::new("Admin").command(Command::new("exit"));
+
+    assert_eq!(
+        run(&app, "exit now\nquit\n"),
+        "Admin> exit [\"now\"]\nAdmin> "
+    );
+}
+
+#[test]
+fn completion() {
+    let app = app();
+
+    assert_eq!(app.complete(""), ["user", "restart"]);
+    assert_eq!(app.complete("re"), ["restart"]);
+    assert_eq!(app.complete("user "), ["add", "remove"]);
+    assert_eq!(
+        app.complete("user add --"),
+        ["--admin", "--color", "--no-color"]
+    );
+    assert_eq!(app.complete("user add --no"), ["--no-color"]);
+    assert_eq!(app.complete("user add name "), Vec::<String>::new());
+    assert_eq!(app.complete("nope "), Vec::<String>::new());
+
+    assert_eq!(run(&app, "user a\t\n"), "Admin> add\nAdmin> ");
+}
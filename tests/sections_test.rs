@@ -0,0 +1,187 @@
+use ecp::builder::{App, Command, Flag};
+
+fn app() -> App {
+    App::new("Rust")
+        .version("1.0.0")
+        .term_width(80)
+        .command(
+            Command::new("cargo")
+                .description("Rust's package manager")
+                .subcommand(
+                    Command::new("build")
+                        .description("Compile the current package")
+                        .flag(
+                            Flag::new("release")
+                                .short('r')
+                                .description("Build in release mode"),
+                        )
+                        .example("rust cargo build -r", "Build optimized artifacts")
+                        .example("rust cargo build", "")
+                        .section(
+                            "Environment",
+                            "CARGO_HOME   Where caches live\nCARGO_LOG    Log filter",
+                        ),
+                ),
+        )
+        .section("See also", "rustc(1), rustup(1)")
+}
+
+#[test]
+fn command_help() {
+    assert_eq!(
+        app().render_command_help(&["cargo", "build"]).unwrap(),
+        concat!(
+            "Compile the current package\n",
+            "\n",
+            "Usage: Rust cargo build [FLAGS]\n",
+            "\n",
+            "Flags:\n",
+            "  -r, --release  Build in release mode\n",
+            "\n",
+            "Examples:\n",
+            "  $ rust cargo build -r\n",
+            "    Build optimized artifacts\n",
+            "  $ rust cargo build\n",
+            "\n",
+            "Environment:\n",
+            "  CARGO_HOME   Where caches live\n",
+            "  CARGO_LOG    Log filter\n",
+        )
+    );
+}
+
+#[test]
+fn app_help() {
+    assert!(app().render_help().ends_with(concat!(
+        "Commands:\n",
+        "  cargo  Rust's package manager\n",
+        "\n",
+        "See also:\n",
+        "  rustc(1), rustup(1)\n",
+    )));
+}
+
+#[test]
+fn templates() {
+    let app = app().help_template("{name} {version}\n{usage}\n\n{sections}");
+
+    assert_eq!(
+        app.render_help(),
+        "Rust 1.0.0\nUsage: Rust <COMMAND>\n\nSee also:\n  rustc(1), rustup(1)\n"
+    );
+    assert_eq!(
+        app.render_command_help(&["cargo"]).unwrap(),
+        "Rust 1.0.0\nUsage: Rust cargo [SUBCOMMAND]\n\n"
+    );
+
+    let app = App::new("Rust").help_template("{usage}").command(
+        Command::new("cargo")
+            .description("Rust's package manager")
+            .help_template("{description}\n{subcommands}{flags}"),
+    );
+
+    assert_eq!(
+        app.render_command_help(&["cargo"]).unwrap(),
+        "Rust's package manager\n"
+    );
+    assert_eq!(app.render_help(), "Usage: Rust <COMMAND>\n");
+
+    let app = App::new("Rust").command(
+        Command::new("cargo")
+            .description("Replaces {flags} and {name} in {a}")
+            .help_template("{{description} {name}}"),
+    );
+
+    assert_eq!(
+        app.render_command_help(&["cargo"]).unwrap(),
+        "{Replaces {flags} and {name} in {a} Rust}\n"
+    );
+}
+
+#[test]
+fn man_page() {
+    let man = app().render_man();
+
+    assert!(man.starts_with(".TH RUST 1 \"\" \"Rust 1.0.0\"\n"));
+    assert!(man.contains(concat!(
+        ".SS \"Rust cargo build\"\n",
+        "Compile the current package\n",
+        ".PP\n",
+        "Usage: Rust cargo build [FLAGS]\n",
+        ".TP\n",
+        ".B \\-r, \\-\\-release\n",
+        "Build in release mode\n",
+        ".PP\n",
+        ".B \"Environment\"\n",
+        ".nf\n",
+        "CARGO_HOME   Where caches live\n",
+        "CARGO_LOG    Log filter\n",
+        ".fi\n",
+    )));
+    assert!(man.ends_with(concat!(
+        ".SH EXAMPLES\n",
+        ".TP\n",
+        ".B rust cargo build \\-r\n",
+        "Build optimized artifacts\n",
+        ".TP\n",
+        ".B rust cargo build\n",
+        ".SH \"SEE ALSO\"\n",
+        ".nf\n",
+        "rustc(1), rustup(1)\n",
+        ".fi\n",
+    )));
+}
+
+#[test]
+fn man_escapes() {
+    let app = App::new("Tool")
+        .command(Command::new("run").description(".starts with a dot and has a \\ backslash"));
+
+    assert!(
+        app.render_man()
+            .contains("\\&.starts with a dot and has a \\e backslash\n")
+    );
+}
+
+#[test]
+fn man_escapes_quoted_arguments() {
+    let app = App::new("Tool")
+        .version("1.0 \"beta\"")
+        .section("The \"fine\" print", "None")
+        .command(
+            Command::new("run")
+                .flag(Flag::new("fast").help_heading("\"Fast\" flags"))
+                .section("On \"run\"", "None"),
+        );
+    let man = app.render_man();
+
+    assert!(man.contains(".TH TOOL 1 \"\" \"Tool 1.0 \\(dqbeta\\(dq\"\n"));
+    assert!(man.contains(".SH \"THE \\(dqFINE\\(dq PRINT\"\n"));
+    assert!(man.contains(".B \"\\(dqFast\\(dq flags\"\n"));
+    assert!(man.contains(".B \"On \\(dqrun\\(dq\"\n"));
+}
+
+#[test]
+fn markdown() {
+    let markdown = app().render_markdown();
+
+    assert!(markdown.starts_with("# Rust\n\nVersion: `1.0.0`\n\n```\nRust <COMMAND>\n```\n\n"));
+    assert!(markdown.contains(concat!(
+        "### `Rust cargo build`\n\n",
+        "Compile the current package\n\n",
+        "```\nRust cargo build [FLAGS]\n```\n\n",
+        "| Flag | Description |\n",
+        "| --- | --- |\n",
+        "| `-r, --release` | Build in release mode |\n\n",
+        "#### Examples\n\n",
+        "Build optimized artifacts\n\n",
+        "```\nrust cargo build -r\n```\n\n",
+        "```\nrust cargo build\n```\n\n",
+        "#### Environment\n\n",
+    )));
+    assert!(markdown.ends_with("## See also\n\nrustc(1), rustup(1)\n\n"));
+
+    let app =
+        App::new("Sh").command(Command::new("pipe").flag(Flag::new("filter").description("a | b")));
+    assert!(app.render_markdown().contains("| `--filter` | a \\| b |\n"));
+}
@@ -0,0 +1,187 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn tools() -> App {
+    App::new("Tools")
+        .command(
+            Command::new("cargo")
+                .flag(Flag::new("verbose").short('v'))
+                .subcommand(Command::new("build").flag(Flag::new("release").short('r')))
+                .subcommand(
+                    Command::new("help")
+                        .subcommand(Command::new("build"))
+                        .subcommand(Command::new("run")),
+                ),
+        )
+        .command(Command::new("ls").flag(Flag::new("long").short('l')))
+}
+
+#[test]
+fn without_flags() {
+    let cli = tools().try_parse_from(["tools", "cargo", "build"]).unwrap();
+
+    assert_eq!(cli.get_command(), "cargo");
+    assert_eq!(cli.get_subcommand(), Some("build"));
+    assert_eq!(cli.get_flags().count(), 0);
+
+    let cli = tools().try_parse_from(["tools", "cargo"]).unwrap();
+
+    assert_eq!(cli.get_command(), "cargo");
+    assert_eq!(cli.get_subcommand(), None);
+}
+
+#[test]
+fn without_subcommands() {
+    let cli = tools()
+        .try_parse_from(["tools", "ls", "-l", "src"])
+        .unwrap();
+
+    assert_eq!(cli.get_command(), "ls");
+    assert_eq!(cli.get_subcommand(), None);
+    assert!(cli.get_flags().any(|f| f == "long"));
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["src"]);
+}
+
+#[test]
+fn flags_before_subcommand() {
+    let cli = tools()
+        .try_parse_from(["tools", "cargo", "-v", "build", "-r"])
+        .unwrap();
+
+    assert_eq!(cli.get_subcommand(), Some("build"));
+    assert_eq!(
+        cli.get_flags().collect::<Vec<_>>(),
+        vec!["verbose", "release"]
+    );
+
+    let cli = tools().try_parse_from(["tools", "cargo", "-v"]).unwrap();
+
+    assert_eq!(cli.get_subcommand(), None);
+    assert!(cli.get_flags().any(|f| f == "verbose"));
+
+    // Flags belong to the command they follow
+    assert!(matches!(
+        tools().try_parse_from(["tools", "cargo", "-r", "build"]),
+        Err(AppError::InvalidFlag(_))
+    ));
+}
+
+#[test]
+fn nested_subcommands() {
+    let cli = tools()
+        .try_parse_from(["tools", "cargo", "help", "run"])
+        .unwrap();
+
+    assert_eq!(cli.get_subcommand(), Some("help"));
+    assert_eq!(
+        cli.get_command_path().collect::<Vec<_>>(),
+        vec!["cargo", "help", "run"]
+    );
+}
+
+#[test]
+fn invalid_shapes() {
+    assert!(matches!(
+        tools().try_parse_from(Vec::<&str>::new()),
+        Err(AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        tools().try_parse_from(["tools"]),
+        Err(AppError::InvalidInput(_))
+    ));
+    assert!(matches!(
+        tools().try_parse_from(["tools", "-v"]),
+        Err(AppError::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        tools().try_parse_from(["tools", "cargo", "biuld"]),
+        Err(AppError::InvalidCommand(_))
+    ));
+    assert!(matches!(
+        tools().try_parse_from(["tools", "ls", "build", "-r"]),
+        Err(AppError::InvalidFlag(_))
+    ));
+}
+
+#[test]
+fn short_clusters() {
+    let app = App::new("Tools").command(
+        Command::new("ls")
+            .flag(Flag::new("long").short('l'))
+            .flag(Flag::new("all").short('a'))
+            .flag(Flag::new("width").short('w').takes_value(true)),
+    );
+
+    let cli = app.try_parse_from(["tools", "ls", "-la"]).unwrap();
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), ["long", "all"]);
+
+    // The rest of the cluster after a flag that takes a value is the value
+    let cli = app.try_parse_from(["tools", "ls", "-lw80", "src"]).unwrap();
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), ["long", "width"]);
+    assert_eq!(cli.get_flag_value("width"), Some("80"));
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), ["src"]);
+
+    let cli = app.try_parse_from(["tools", "ls", "-law", "80"]).unwrap();
+    assert_eq!(cli.get_flag_value("width"), Some("80"));
+
+    for (arg, unknown) in [("-lx", "-x"), ("-lzzz", "-z")] {
+        match app.try_parse_from(["tools", "ls", arg]) {
+            Err(AppError::InvalidFlag(msg)) => assert!(msg.contains(unknown)),
+            _ => panic!("expected `{}` to have an unknown flag", arg),
+        }
+    }
+    assert!(matches!(
+        app.try_parse_from(["tools", "ls", "-l=yes"]),
+        Err(AppError::UnexpectedValue(flag)) if flag == "long"
+    ));
+}
+
+#[test]
+fn end_of_flags() {
+    let cli = tools()
+        .try_parse_from(["tools", "ls", "-l", "--", "-l", "--long", "--"])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_count("long"), 1);
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), ["-l", "--long", "--"]);
+
+    let cli = tools()
+        .try_parse_from(["tools", "cargo", "--", "build"])
+        .unwrap();
+
+    assert_eq!(cli.get_subcommand(), None);
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), ["build"]);
+}
+
+#[test]
+fn flags_belong_to_their_command() {
+    let app = App::new("Rust").command(
+        Command::new("cargo")
+            .flag(Flag::new("verbose").max_occurrences(1))
+            .flag(Flag::new("color").negatable(true))
+            .subcommand(
+                Command::new("build")
+                    .flag(Flag::new("verbose").max_occurrences(1))
+                    .flag(Flag::new("color").negatable(true).enabled_by_default(true)),
+            ),
+    );
+
+    let cli = app
+        .try_parse_from([
+            "rust",
+            "cargo",
+            "--verbose",
+            "--no-color",
+            "build",
+            "--verbose",
+        ])
+        .unwrap();
+
+    assert_eq!(cli.get_flag_count("verbose"), 1);
+    assert_eq!(cli.get_flag_indices("verbose").collect::<Vec<_>>(), [5]);
+    assert_eq!(cli.get_flag_state("color"), Some(true));
+    assert!(matches!(
+        app.try_parse_from(["rust", "cargo", "build", "--verbose", "--verbose"]),
+        Err(AppError::InvalidOccurrences(_))
+    ));
+}
@@ -0,0 +1,76 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn wrap() -> App {
+    App::new("Wrap").command(
+        Command::new("wrap").subcommand(
+            Command::new("run")
+                .flag(Flag::new("verbose").short('v'))
+                .flag(Flag::new("color").negatable(true)),
+        ),
+    )
+}
+
+#[test]
+fn unknown_flags_are_errors() {
+    for flag in ["-x", "--foo", "--no-verbose", "--verbosity=2"] {
+        match wrap().try_parse_from(["wrap", "wrap", "run", "-v", flag]) {
+            Err(AppError::InvalidFlag(msg)) => assert!(msg.contains(flag)),
+            _ => panic!("expected `{}` to be an unknown flag", flag),
+        }
+    }
+}
+
+#[test]
+fn no_flags_is_not_an_error() {
+    let cli = wrap()
+        .try_parse_from(["wrap", "wrap", "run", "main.rs"])
+        .unwrap();
+
+    assert_eq!(cli.get_flags().count(), 0);
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["main.rs"]);
+}
+
+#[test]
+fn pass_through_unknown_flags() {
+    let cli = wrap()
+        .allow_unknown(true)
+        .try_parse_from([
+            "wrap",
+            "wrap",
+            "run",
+            "--foo=bar",
+            "-v",
+            "main.rs",
+            "-x",
+            "--no-color",
+        ])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_unknown_flags().collect::<Vec<_>>(),
+        vec!["--foo=bar", "-x"]
+    );
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["main.rs"]);
+    assert!(cli.get_flags().any(|f| f == "verbose"));
+    assert_eq!(cli.get_flag_state("color"), Some(false));
+}
+
+#[test]
+fn unknown_flags_in_short_clusters() {
+    match wrap().try_parse_from(["wrap", "wrap", "run", "-vx"]) {
+        Err(AppError::InvalidFlag(msg)) => assert!(msg.contains("-x")),
+        _ => panic!("expected `-x` to be an unknown flag"),
+    }
+
+    let cli = wrap()
+        .allow_unknown(true)
+        .try_parse_from(["wrap", "wrap", "run", "-vxy", "-xv", "-vO2"])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_unknown_flags().collect::<Vec<_>>(),
+        ["-xy", "-xv", "-O2"]
+    );
+    assert_eq!(cli.get_flag_count("verbose"), 2);
+}
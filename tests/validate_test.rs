@@ -0,0 +1,114 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::{AppError, ValidationProblem};
+
+#[test]
+fn valid_app() {
+    let cli = App::new("Rust").command(
+        Command::new("cargo")
+            .subcommand(Command::new("build").flag(Flag::new("release").short('r')))
+            .subcommand(Command::new("run").flag(Flag::new("release").short('r'))),
+    );
+
+    assert!(cli.validate().is_ok());
+}
+
+#[test]
+fn reports_every_problem() {
+    let cli = App::new("Rust")
+        .command(
+            Command::new("cargo").subcommand(
+                Command::new("run")
+                    .flag(Flag::new("release").short('r'))
+                    .flag(Flag::new("release"))
+                    .flag(Flag::new("registry").short('r'))
+                    .flag(Flag::new(""))
+                    .flag(Flag::new("config").key_value(true).value_delimiter('=')),
+            ),
+        )
+        .command(Command::new("cargo"))
+        .command(Command::new("--rustc"));
+
+    let problems = match cli.validate() {
+        Err(AppError::InvalidDefinition(problems)) => problems,
+        _ => panic!("expected an invalid definition"),
+    };
+
+    assert_eq!(
+        problems,
+        vec![
+            ValidationProblem::DuplicateFlag {
+                scope: "Rust cargo run".to_string(),
+                long: "release".to_string(),
+            },
+            ValidationProblem::ShortCollision {
+                scope: "Rust cargo run".to_string(),
+                short: 'r',
+            },
+            ValidationProblem::InvalidFlagName {
+                scope: "Rust cargo run".to_string(),
+                long: "".to_string(),
+            },
+            ValidationProblem::InvalidDelimiter {
+                scope: "Rust cargo run".to_string(),
+                long: "config".to_string(),
+            },
+            ValidationProblem::DuplicateCommand {
+                scope: "Rust".to_string(),
+                name: "cargo".to_string(),
+            },
+            ValidationProblem::InvalidCommandName {
+                scope: "Rust".to_string(),
+                name: "--rustc".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn parse_validates_in_debug() {
+    let args: Vec<std::ffi::OsString> = vec!["ecp".into(), "cargo".into()];
+
+    let cli = App::new("Rust")
+        .command(Command::new("cargo"))
+        .command(Command::new("cargo"));
+
+    assert!(matches!(
+        cli.try_parse_args(args),
+        Err(AppError::InvalidDefinition(_))
+    ));
+}
+
+#[test]
+fn aliases_collide() {
+    let cli = App::new("Rust").command(
+        Command::new("cargo")
+            .subcommand(Command::new("build").alias("b"))
+            .subcommand(Command::new("bench").visible_alias("b"))
+            .flag(Flag::new("release").short('r'))
+            .flag(Flag::new("rel").alias("release").short_alias('r')),
+    );
+
+    let problems = match cli.validate() {
+        Err(AppError::InvalidDefinition(problems)) => problems,
+        _ => panic!("expected an invalid definition"),
+    };
+
+    assert_eq!(
+        problems,
+        vec![
+            ValidationProblem::DuplicateFlag {
+                scope: "Rust cargo".to_string(),
+                long: "release".to_string(),
+            },
+            ValidationProblem::ShortCollision {
+                scope: "Rust cargo".to_string(),
+                short: 'r',
+            },
+            ValidationProblem::DuplicateCommand {
+                scope: "Rust cargo".to_string(),
+                name: "b".to_string(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,119 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::AppError;
+
+fn gcc() -> App {
+    App::new("GCC").command(
+        Command::new("cc").subcommand(
+            Command::new("build")
+                .flag(Flag::new("include").short('I').takes_value(true))
+                .flag(Flag::new("tags").value_delimiter(',').max_occurrences(1))
+                .flag(
+                    Flag::new("output")
+                        .short('o')
+                        .takes_value(true)
+                        .min_occurrences(1),
+                )
+                .flag(Flag::new("verbose").short('v')),
+        ),
+    )
+}
+
+#[test]
+fn repeated_values() {
+    let cli = gcc()
+        .try_parse_from([
+            "gcc",
+            "cc",
+            "build",
+            "-I",
+            "src",
+            "main.c",
+            "-Iinclude",
+            "--include=lib",
+            "--tags",
+            "a,b,c",
+            "-o",
+            "main",
+            "-v",
+            "-v",
+        ])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_flag_values("include").collect::<Vec<_>>(),
+        vec!["src", "include", "lib"]
+    );
+    assert_eq!(
+        cli.get_flag_indices("include").collect::<Vec<_>>(),
+        vec![3, 6, 7]
+    );
+    assert_eq!(
+        cli.get_flag_values("tags").collect::<Vec<_>>(),
+        vec!["a", "b", "c"]
+    );
+    assert_eq!(
+        cli.get_flag_values("output").collect::<Vec<_>>(),
+        vec!["main"]
+    );
+    assert_eq!(cli.get_flag_count("verbose"), 2);
+    assert_eq!(cli.get_flag_values("verbose").count(), 0);
+    assert_eq!(cli.get_values().collect::<Vec<_>>(), vec!["main.c"]);
+    assert_eq!(
+        cli.get_flags().collect::<Vec<_>>(),
+        vec!["include", "tags", "output", "verbose"]
+    );
+}
+
+#[test]
+fn occurrence_limits() {
+    assert!(matches!(
+        gcc().try_parse_from(["gcc", "cc", "build", "-I", "src"]),
+        Err(AppError::InvalidOccurrences(_))
+    ));
+    assert!(matches!(
+        gcc().try_parse_from([
+            "gcc", "cc", "build", "-o", "main", "--tags", "a", "--tags", "b",
+        ]),
+        Err(AppError::InvalidOccurrences(_))
+    ));
+}
+
+#[test]
+fn value_errors() {
+    assert!(matches!(
+        gcc().try_parse_from(["gcc", "cc", "build", "-o"]),
+        Err(AppError::MissingValue(flag)) if flag == "output"
+    ));
+    assert!(matches!(
+        gcc().try_parse_from(["gcc", "cc", "build", "-o", "-v"]),
+        Err(AppError::MissingValue(flag)) if flag == "output"
+    ));
+    assert!(matches!(
+        gcc().try_parse_from(["gcc", "cc", "build", "-o", "main", "--verbose=yes"]),
+        Err(AppError::UnexpectedValue(flag)) if flag == "verbose"
+    ));
+}
+
+#[test]
+fn getters_agree_with_the_flag_state() {
+    let app = App::new("Ls").command(
+        Command::new("ls")
+            .flag(Flag::new("color").negatable(true))
+            .flag(Flag::new("width").takes_value(true))
+            .subcommand(Command::new("tree").flag(Flag::new("width").takes_value(true))),
+    );
+
+    let cli = app
+        .try_parse_from(["ls", "ls", "--color", "--no-color", "--no-color"])
+        .unwrap();
+    assert_eq!(cli.get_flag_state("color"), Some(false));
+    assert_eq!(cli.get_flag_count("color"), 1);
+    assert_eq!(cli.get_flag_indices("color").collect::<Vec<_>>(), [2]);
+
+    // The flag of the subcommand shadows the flag of its parent
+    let cli = app
+        .try_parse_from(["ls", "ls", "--width", "1", "tree", "--width", "2"])
+        .unwrap();
+    assert_eq!(cli.get_flag_values("width").collect::<Vec<_>>(), ["2"]);
+    assert_eq!(cli.get_flag_count("width"), 1);
+}
@@ -0,0 +1,182 @@
+use ecp::builder::{App, Command, Flag};
+use ecp::errors::{AppError, ErrorKind};
+
+fn app() -> App {
+    App::new("Rust")
+        .term_width(80)
+        .unstable_flag("unstable")
+        .command(
+            Command::new("cargo")
+                .flag(Flag::new("locked").description("Require Cargo.lock"))
+                .flag(
+                    Flag::new("frozen")
+                        .description("Same as --locked")
+                        .deprecated("use `--locked` instead"),
+                )
+                .flag(Flag::new("trace-parser").hidden(true))
+                .flag(
+                    Flag::new("build-std")
+                        .description("Build the standard library")
+                        .experimental(true),
+                )
+                .subcommand(Command::new("build"))
+                .subcommand(Command::new("debug-dump").hidden(true))
+                .subcommand(Command::new("fix").deprecated(""))
+                .subcommand(Command::new("script").experimental(true)),
+        )
+        .command(Command::new("internal").hidden(true))
+}
+
+#[test]
+fn hidden_items_are_left_out_of_help() {
+    let app = app();
+
+    assert!(!app.render_help().contains("internal"));
+    assert_eq!(
+        app.render_command_help(&["cargo"]).unwrap(),
+        concat!(
+            "Usage: Rust cargo [FLAGS] [SUBCOMMAND]\n",
+            "\n",
+            "Subcommands:\n",
+            "  build\n",
+            "  fix     [deprecated]\n",
+            "  script  [experimental]\n",
+            "\n",
+            "Flags:\n",
+            "      --locked     Require Cargo.lock\n",
+            "      --frozen     Same as --locked [deprecated]\n",
+            "      --build-std  Build the standard library [experimental]\n",
+        )
+    );
+    assert!(!app.render_man().contains("debug"));
+    assert!(!app.render_markdown().contains("trace-parser"));
+}
+
+#[test]
+fn hidden_items_are_not_completed_or_suggested() {
+    let app = app();
+
+    assert_eq!(app.complete("cargo d"), Vec::<String>::new());
+    assert_eq!(app.complete("cargo --tr"), Vec::<String>::new());
+    assert_eq!(app.complete("i"), Vec::<String>::new());
+
+    let diagnostics = app
+        .try_parse_diagnostics_from(["rust", "cargo", "--trace-parsr"])
+        .err()
+        .unwrap();
+    assert!(diagnostics[0].get_suggestions().is_empty());
+}
+
+#[test]
+fn hidden_items_are_still_accepted() {
+    let cli = app()
+        .try_parse_from(["rust", "cargo", "--trace-parser", "debug-dump"])
+        .unwrap();
+
+    assert_eq!(cli.get_subcommand(), Some("debug-dump"));
+    assert_eq!(cli.get_flags().collect::<Vec<_>>(), ["trace-parser"]);
+}
+
+#[test]
+fn deprecated_items_warn() {
+    let cli = app()
+        .try_parse_from(["rust", "cargo", "--frozen", "--frozen", "fix"])
+        .unwrap();
+
+    assert_eq!(
+        cli.get_warnings().collect::<Vec<_>>(),
+        [
+            "`--frozen` is deprecated, use `--locked` instead",
+            "`fix` is deprecated"
+        ]
+    );
+    assert!(cli.to_json().ends_with(
+        r#""warnings":["`--frozen` is deprecated, use `--locked` instead","`fix` is deprecated"]}"#
+    ));
+
+    let cli = app().try_parse_from(["rust", "cargo", "build"]).unwrap();
+    assert_eq!(cli.get_warnings().count(), 0);
+}
+
+#[test]
+fn deprecated_items_warn_in_the_repl() {
+    let mut output = Vec::new();
+    app()
+        .repl("cargo --frozen build\n".as_bytes(), &mut output, |_, _| {
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Rust> Warning: `--frozen` is deprecated, use `--locked` instead\nRust> "
+    );
+}
+
+#[test]
+fn experimental_items_need_the_unstable_gate() {
+    let app = app();
+
+    match app.try_parse_from(["rust", "cargo", "script"]) {
+        Err(e @ AppError::Experimental(_)) => {
+            assert_eq!(
+                e.to_string(),
+                "Error: Experimental: `script` is experimental, pass `--unstable` to use it"
+            );
+            assert_eq!(e.kind(), ErrorKind::Experimental);
+            assert_eq!(e.exit_code(), 64);
+        }
+        _ => panic!("expected an experimental error"),
+    }
+
+    let diagnostics = app
+        .try_parse_diagnostics_from(["rust", "cargo", "--locked", "--build-std", "build"])
+        .err()
+        .unwrap();
+    assert_eq!(diagnostics[0].get_token(), Some("--build-std"));
+    assert_eq!(diagnostics[0].get_position(), Some(3));
+
+    for args in [
+        &["rust", "--unstable", "cargo", "script"][..],
+        &["rust", "cargo", "--build-std", "build", "--unstable"],
+    ] {
+        assert!(app.try_parse_from(args).is_ok(), "{:?}", args);
+    }
+}
+
+#[test]
+fn experimental_errors_are_collected() {
+    let app = app().collect_errors(true);
+
+    match app.try_parse_from(["rust", "cargo", "--build-std", "--nope", "script"]) {
+        Err(AppError::Multiple(errors)) => {
+            let kinds: Vec<ErrorKind> = errors.iter().map(AppError::kind).collect();
+            assert_eq!(
+                kinds,
+                [
+                    ErrorKind::InvalidFlag,
+                    ErrorKind::Experimental,
+                    ErrorKind::Experimental
+                ]
+            );
+        }
+        _ => panic!("expected multiple errors"),
+    }
+}
+
+#[test]
+fn unstable_env_gate() {
+    let app = App::new("Rust")
+        .unstable_env("ECP_TEST_UNSET_UNSTABLE")
+        .command(Command::new("script").experimental(true));
+
+    assert_eq!(app.get_unstable_env(), Some("ECP_TEST_UNSET_UNSTABLE"));
+    assert!(matches!(
+        app.try_parse_from(["rust", "script"]),
+        Err(AppError::Experimental(msg)) if msg.ends_with("set `ECP_TEST_UNSET_UNSTABLE=1` to use it")
+    ));
+    assert!(matches!(
+        app.try_parse_from(["rust", "--unstable", "script"]),
+        Err(AppError::InvalidCommand(_))
+    ));
+}
@@ -0,0 +1,132 @@
+use ecp::builder::{App, Command, Flag};
+
+fn app(width: usize) -> App {
+    App::new("Rust").term_width(width).command(
+        Command::new("build")
+            .description("Compile a local package and all of its dependencies")
+            .flag(
+                Flag::new("release")
+                    .short('r')
+                    .description("Build optimized artifacts with the release profile"),
+            )
+            .flag(
+                Flag::new("jobs")
+                    .takes_value(true)
+                    .description("Number of parallel jobs"),
+            ),
+    )
+}
+
+#[test]
+fn wraps_under_the_description_column() {
+    assert_eq!(
+        app(50).render_command_help(&["build"]).unwrap(),
+        concat!(
+            "Compile a local package and all of its\n",
+            "dependencies\n",
+            "\n",
+            "Usage: Rust build [FLAGS]\n",
+            "\n",
+            "Flags:\n",
+            "  -r, --release       Build optimized artifacts\n",
+            "                      with the release profile\n",
+            "      --jobs <VALUE>  Number of parallel jobs\n",
+        )
+    );
+}
+
+#[test]
+fn wide_terminals_keep_one_line() {
+    let help = app(120).render_command_help(&["build"]).unwrap();
+
+    assert!(
+        help.contains("  -r, --release       Build optimized artifacts with the release profile\n")
+    );
+}
+
+#[test]
+fn narrow_terminals_move_descriptions_below() {
+    assert_eq!(
+        app(30).render_command_help(&["build"]).unwrap(),
+        concat!(
+            "Compile a local package and\n",
+            "all of its dependencies\n",
+            "\n",
+            "Usage: Rust build [FLAGS]\n",
+            "\n",
+            "Flags:\n",
+            "  -r, --release\n",
+            "        Build optimized\n",
+            "        artifacts with the\n",
+            "        release profile\n",
+            "      --jobs <VALUE>\n",
+            "        Number of parallel\n",
+            "        jobs\n",
+        )
+    );
+}
+
+#[test]
+fn unicode_display_width() {
+    let app = App::new("Tools")
+        .term_width(40)
+        .command(Command::new("構築").description("すべてをビルド"))
+        .command(Command::new("café").description("Brews coffee ☕ for everyone"))
+        .command(Command::new("run").description("Runs it"));
+
+    assert_eq!(
+        app.render_help(),
+        concat!(
+            "Tools\n",
+            "\n",
+            "Usage: Tools <COMMAND>\n",
+            "\n",
+            "Commands:\n",
+            "  構築  すべてをビルド\n",
+            "  café  Brews coffee ☕ for everyone\n",
+            "  run   Runs it\n",
+        )
+    );
+}
+
+#[test]
+fn long_words_are_not_split() {
+    let app = App::new("Curl")
+        .term_width(40)
+        .command(Command::new("get").flag(
+            Flag::new("url").description("Defaults to https://example.com/a/very/long/path"),
+        ));
+
+    assert!(app.render_command_help(&["get"]).unwrap().ends_with(
+        "      --url  Defaults to\n             https://example.com/a/very/long/path\n"
+    ));
+}
+
+#[test]
+fn wide_names_keep_columns_aligned() {
+    let app = App::new("Tools").term_width(40).command(
+        Command::new("🚀")
+            .description("Launches it")
+            .subcommand(Command::new("🪐").description("Orbits"))
+            .subcommand(Command::new("land").description("Lands"))
+            .flag(Flag::new("fast").short('速').description("Goes fast"))
+            .flag(Flag::new("slow").description("Goes slow")),
+    );
+
+    assert_eq!(
+        app.render_command_help(&["🚀"]).unwrap(),
+        concat!(
+            "Launches it\n",
+            "\n",
+            "Usage: Tools 🚀 [FLAGS] [SUBCOMMAND]\n",
+            "\n",
+            "Subcommands:\n",
+            "  🪐    Orbits\n",
+            "  land  Lands\n",
+            "\n",
+            "Flags:\n",
+            "  -速, --fast  Goes fast\n",
+            "      --slow   Goes slow\n",
+        )
+    );
+}